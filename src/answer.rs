@@ -0,0 +1,143 @@
+use std::{
+    fmt::Display,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+/// Answer of a single part.
+///
+/// Integers are compared by value regardless of the variant holding them, so `Int(5)` equals `BigInt(5)`.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Int(i64),
+    BigInt(i128),
+    Text(String),
+}
+
+impl Answer {
+    /// Integer value of the answer, `None` for text answers.
+    pub fn as_i128(&self) -> Option<i128> {
+        match self {
+            Answer::Int(v) => Some(i128::from(*v)),
+            Answer::BigInt(v) => Some(*v),
+            Answer::Text(_) => None,
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::Text(a), Answer::Text(b)) => a == b,
+            (a, b) => a.as_i128().is_some_and(|a| Some(a) == b.as_i128()),
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl Hash for Answer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Answer::Text(v) => v.hash(state),
+            v => v.as_i128().hash(state),
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(v) => write!(f, "{}", v),
+            Answer::BigInt(v) => write!(f, "{}", v),
+            Answer::Text(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+impl FromStr for Answer {
+    type Err = std::convert::Infallible;
+
+    /// Integers become [`Answer::Int`] or [`Answer::BigInt`] depending on their size, anything else is text.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.parse::<i128>() {
+            Ok(v) => v.into(),
+            Err(_) => Answer::Text(s.to_string()),
+        })
+    }
+}
+
+impl From<i128> for Answer {
+    fn from(value: i128) -> Self {
+        i64::try_from(value).map_or(Answer::BigInt(value), Answer::Int)
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        i128::try_from(value).map_or_else(|_| Answer::Text(value.to_string()), Answer::from)
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::from(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+
+    use super::*;
+
+    fn hash(answer: &Answer) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        answer.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn integers_compare_by_value() {
+        assert_eq!(Answer::Int(5), Answer::BigInt(5));
+        assert_eq!(hash(&Answer::Int(5)), hash(&Answer::BigInt(5)));
+        assert_eq!(Answer::Int(-7), Answer::BigInt(-7));
+        assert_eq!(hash(&Answer::Int(-7)), hash(&Answer::BigInt(-7)));
+        assert_ne!(Answer::Int(5), Answer::BigInt(6));
+        assert_ne!(Answer::Int(5), Answer::from("5"));
+        assert_eq!(Answer::from("abc"), Answer::Text("abc".to_string()));
+        assert_eq!(hash(&Answer::from("abc")), hash(&Answer::Text("abc".to_string())));
+    }
+
+    #[test]
+    fn conversions() {
+        assert!(matches!(Answer::from(5u64), Answer::Int(5)));
+        assert!(matches!(Answer::from(u64::MAX), Answer::BigInt(_)));
+        assert!(matches!(Answer::from(u128::MAX), Answer::Text(_)));
+        assert!(matches!("-12".parse(), Ok(Answer::Int(-12))));
+        assert!(matches!(i128::MAX.to_string().parse(), Ok(Answer::BigInt(i128::MAX))));
+        assert!(matches!("12a".parse(), Ok(Answer::Text(_))));
+        assert_eq!(Answer::from(u128::MAX).to_string(), u128::MAX.to_string());
+        assert_eq!(Answer::from(u64::MAX).to_string(), u64::MAX.to_string());
+    }
+}
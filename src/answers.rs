@@ -0,0 +1,141 @@
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{bail, Context, Result};
+
+use crate::{answer::Answer, solution::Part};
+
+/// Known answers of a single day, read from `answers/<day>.toml`:
+///
+/// ```toml
+/// part1 = 55816
+/// part2 = "54980"
+/// ```
+///
+/// Strings holding integers are read as integers, so both parts above compare equal to integer answers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    part1: Option<Answer>,
+    part2: Option<Answer>,
+}
+
+impl Answers {
+    pub fn path(dir: impl AsRef<Path>, day: usize) -> PathBuf {
+        dir.as_ref().join(format!("{}.toml", day))
+    }
+
+    /// Loads the answers of `day`, a missing file means no answers are known yet.
+    pub fn load(dir: impl AsRef<Path>, day: usize) -> Result<Self> {
+        let path = Self::path(dir, day);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        std::fs::read_to_string(&path)
+            .with_context(|| format!("Cannot read answers file '{}'", path.display()))?
+            .parse()
+            .with_context(|| format!("Invalid answers file '{}'", path.display()))
+    }
+
+    pub fn get(&self, part: Part) -> Option<&Answer> {
+        match part {
+            Part::One => self.part1.as_ref(),
+            Part::Two => self.part2.as_ref(),
+        }
+    }
+
+    pub fn check(&self, part: Part, answer: &Answer) -> Status {
+        match self.get(part) {
+            Some(expected) if expected == answer => Status::Pass,
+            Some(_) => Status::Fail,
+            None => Status::Unknown,
+        }
+    }
+}
+
+impl FromStr for Answers {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut res = Answers::default();
+        for (key, value) in s.parse::<toml::Table>()? {
+            let value = match value {
+                toml::Value::String(v) => v.parse::<Answer>()?,
+                toml::Value::Integer(v) => Answer::from(v),
+                v => bail!("Invalid answer for '{}': {}, expected a string or an integer", key, v),
+            };
+            match key.as_str() {
+                "part1" => res.part1 = Some(value),
+                "part2" => res.part2 = Some(value),
+                _ => bail!("Unknown key '{}', expected part1 or part2", key),
+            }
+        }
+        Ok(res)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The part succeeded and its answer was not checked.
+    Ok,
+    Pass,
+    Fail,
+    /// The answer was checked but there is no known answer for the part.
+    Unknown,
+    /// The part failed to produce an answer.
+    Error,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Pass => write!(f, "PASS"),
+            Status::Fail => write!(f, "FAIL"),
+            Status::Unknown => write!(f, "UNKNOWN"),
+            Status::Ok => write!(f, "OK"),
+            Status::Error => write!(f, "ERROR"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_answers() {
+        let answers: Answers = "part1 = 55816\npart2 = \"54980\"\n".parse().unwrap();
+        assert_eq!(answers.get(Part::One), Some(&Answer::Int(55816)));
+        assert_eq!(answers.get(Part::Two), Some(&Answer::Int(54980)));
+
+        let answers: Answers = "# Only part 2 is known\npart2 = -3".parse().unwrap();
+        assert_eq!(answers.get(Part::One), None);
+        assert_eq!(answers.get(Part::Two), Some(&Answer::Int(-3)));
+        assert_eq!("".parse::<Answers>().unwrap(), Answers::default());
+    }
+
+    #[test]
+    fn invalid_answers() {
+        for s in [
+            "part3 = 1",
+            "part1 = 1.5",
+            "part1 = [1]",
+            "part1 = true",
+            "part1 =",
+            "part1 = 1\npart1 = 2",
+        ] {
+            assert!(s.parse::<Answers>().is_err(), "'{}' should not parse", s);
+        }
+    }
+
+    #[test]
+    fn check_answers() {
+        let answers: Answers = "part1 = 42".parse().unwrap();
+        assert_eq!(answers.check(Part::One, &Answer::Int(42)), Status::Pass);
+        assert_eq!(answers.check(Part::One, &Answer::BigInt(42)), Status::Pass);
+        assert_eq!(answers.check(Part::One, &Answer::from("42")), Status::Fail);
+        assert_eq!(answers.check(Part::One, &Answer::Int(43)), Status::Fail);
+        assert_eq!(answers.check(Part::Two, &Answer::Int(42)), Status::Unknown);
+    }
+}
@@ -0,0 +1,132 @@
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use itertools::Itertools;
+
+use crate::{
+    answer::Answer,
+    report::Record,
+    solution::{DynSolution, Part},
+};
+
+/// Summary of repeated timings of the same work.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn new(samples: &[Duration]) -> Self {
+        if samples.is_empty() {
+            return Self {
+                min: Duration::ZERO,
+                median: Duration::ZERO,
+                mean: Duration::ZERO,
+                stddev: Duration::ZERO,
+            };
+        }
+
+        let sorted = samples.iter().copied().sorted().collect_vec();
+        let len = sorted.len();
+        let median = if len % 2 == 0 {
+            (sorted[len / 2 - 1] + sorted[len / 2]) / 2
+        } else {
+            sorted[len / 2]
+        };
+        let mean = sorted.iter().map(Duration::as_secs_f64).sum::<f64>() / len as f64;
+        let variance = sorted.iter().map(|v| (v.as_secs_f64() - mean).powi(2)).sum::<f64>() / len as f64;
+
+        Self {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Timings {
+    pub parse: Stats,
+    pub solve: Stats,
+}
+
+/// Parses the input and solves `part` `iterations` times, timing both steps separately.
+pub fn bench(solution: &dyn DynSolution, input: &str, part: Part, iterations: usize) -> Result<(Answer, Timings)> {
+    let mut parse_samples = Vec::with_capacity(iterations);
+    let mut solve_samples = Vec::with_capacity(iterations);
+    let mut answer = None;
+
+    for _ in 0..iterations.max(1) {
+        let start = Instant::now();
+        let parsed = solution.parse(input)?;
+        parse_samples.push(start.elapsed());
+
+        let start = Instant::now();
+        answer = Some(solution.solve(parsed.as_ref(), part)?);
+        solve_samples.push(start.elapsed());
+    }
+
+    let timings = Timings {
+        parse: Stats::new(&parse_samples),
+        solve: Stats::new(&solve_samples),
+    };
+    Ok((answer.expect("At least one iteration to run"), timings))
+}
+
+/// Prints every part followed by the per-day and overall sums of the mean timings.
+pub fn print_summary(records: &[Record], iterations: usize) {
+    println!();
+    println!("Benchmark summary over {} iterations", iterations);
+    println!(
+        "{:<10} | {:>10} {:>10} {:>10} {:>10} | {:>10} {:>10} {:>10} {:>10} | {:>10}",
+        "", "parse min", "median", "mean", "stddev", "solve min", "median", "mean", "stddev", "total mean"
+    );
+
+    let mut total = Duration::ZERO;
+    let benched = records.iter().filter_map(|r| r.timings.map(|t| (r, t)));
+    for (day, parts) in &benched.group_by(|(r, _)| r.day) {
+        let mut day_total = Duration::ZERO;
+        for (res, timings) in parts {
+            let part_total = timings.parse.mean + timings.solve.mean;
+            day_total += part_total;
+            println!(
+                "d{:0>2}p{:<6} | {:>10} {:>10} {:>10} {:>10} | {:>10} {:>10} {:>10} {:>10} | {:>10}",
+                res.day,
+                res.part.number(),
+                format_duration(timings.parse.min),
+                format_duration(timings.parse.median),
+                format_duration(timings.parse.mean),
+                format_duration(timings.parse.stddev),
+                format_duration(timings.solve.min),
+                format_duration(timings.solve.median),
+                format_duration(timings.solve.mean),
+                format_duration(timings.solve.stddev),
+                format_duration(part_total),
+            );
+        }
+        println!(
+            "{:<10} | {:>102}",
+            format!("d{:0>2} total", day),
+            format_duration(day_total)
+        );
+        total += day_total;
+    }
+    println!("{:<10} | {:>102}", "total", format_duration(total));
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs_f64();
+    if secs >= 1.0 {
+        format!("{:.3}s", secs)
+    } else if secs >= 1e-3 {
+        format!("{:.3}ms", secs * 1e3)
+    } else if secs >= 1e-6 {
+        format!("{:.3}µs", secs * 1e6)
+    } else {
+        format!("{}ns", duration.as_nanos())
+    }
+}
@@ -0,0 +1,251 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+
+use crate::{
+    input::{InputSource, Inputs},
+    registry::Registry,
+    report::Format,
+    runner::Config,
+    selection::{Days, Selection},
+};
+
+pub const USAGE: &str = "Usage: aoc23 [OPTIONS]
+       aoc23 new-day <DAY> [TITLE]
+
+Runs the last implemented day when no days are selected.
+
+Commands:
+  new-day <DAY> [TITLE]
+                       Create `src/day<DAY>.rs` with an empty input, answers and a sample test,
+                       and register it
+
+Options:
+  -a, --all            Run every implemented day
+  -d, --day <DAYS>     Run the given days, ie. `5`, `3,7` or `12-15`
+      --days <DAYS>    Alias for --day
+  -p, --part <PART>    Run only part 1 or 2
+  -i, --input <INPUT>  Read input from a file, or stdin when `-`. Prefix with `DAY=` to
+                       override a single day, ie. `--input 5=sample.txt`
+      --input-dir <DIR>
+                       Read inputs from `<DIR>/<day>` instead of `input/<day>`
+  -j, --jobs <N>       Run up to N parts at the same time, output stays in day order
+      --visualize      Draw how each part is solved, for days that support it
+      --trace          Print timing and debug diagnostics of parsing and solving to stderr, also
+                       enabled by setting `AOC_TRACE=1`
+      --bench <N>      Run every part N times and print parse and solve timing statistics
+  -f, --format <FORMAT>
+                       Output format, one of `text` (default), `json` or `csv`
+      --check          Compare answers with `answers/<day>.toml`, fails on a mismatch
+      --answers-dir <DIR>
+                       Read answers from `<DIR>/<day>.toml` instead of `answers/<day>.toml`
+  -h, --help           Print this help";
+
+#[derive(Debug, Default)]
+pub struct Args {
+    pub help: bool,
+    /// Day to scaffold with `new-day` instead of running anything.
+    pub new_day: Option<usize>,
+    pub title: Option<String>,
+    pub selection: Selection,
+    pub inputs: Inputs,
+    /// Input given without a day, applies to the only selected day.
+    pub input: Option<InputSource>,
+    pub check: bool,
+    pub answers_dir: Option<PathBuf>,
+    pub bench: Option<usize>,
+    pub jobs: Option<usize>,
+    pub visualize: bool,
+    /// Print diagnostics to stderr, see [`crate::diagnostics`].
+    pub trace: bool,
+    pub format: Format,
+}
+
+impl Args {
+    /// Parses arguments without the leading program name.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut res = Args::default();
+        let mut args = args.into_iter().peekable();
+
+        if args.next_if(|arg| arg == "new-day").is_some() {
+            let day = args.next().context("Missing day for 'new-day'")?;
+            res.new_day = Some(day.parse().with_context(|| format!("Invalid day '{}'", day))?);
+            res.title = args.next();
+            if let Some(arg) = args.next() {
+                bail!("Unexpected argument '{}'", arg);
+            }
+            return Ok(res);
+        }
+
+        while let Some(arg) = args.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+                _ => (arg, None),
+            };
+            let mut value = || -> Result<String> {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .with_context(|| format!("Missing value for '{}'", flag))
+            };
+
+            match flag.as_str() {
+                "-h" | "--help" => res.help = true,
+                "-a" | "--all" => res.select_days(Days::All)?,
+                "-d" | "--day" | "--days" => {
+                    let days = value()?.parse()?;
+                    res.select_days(days)?;
+                }
+                "-p" | "--part" => {
+                    if res.selection.part.is_some() {
+                        bail!("'{}' can only be given once", flag);
+                    }
+                    res.selection.part = Some(value()?.parse()?);
+                }
+                "-i" | "--input" => {
+                    let value = value()?;
+                    match value.split_once('=').map(|(day, path)| (day.parse::<usize>(), path)) {
+                        Some((Ok(day), path)) => res.inputs.set(day, path.parse()?),
+                        _ if res.input.is_some() => bail!("'{}' without a day can only be given once", flag),
+                        _ => res.input = Some(value.parse()?),
+                    }
+                }
+                "--input-dir" => res.inputs.set_dir(value()?),
+                "--bench" => {
+                    let value = value()?;
+                    let iterations = value
+                        .parse()
+                        .ok()
+                        .filter(|v| *v > 0)
+                        .with_context(|| format!("Invalid iteration count '{}'", value))?;
+                    res.bench = Some(iterations);
+                }
+                "-j" | "--jobs" => {
+                    let value = value()?;
+                    let jobs = value
+                        .parse()
+                        .ok()
+                        .filter(|v| *v > 0)
+                        .with_context(|| format!("Invalid job count '{}'", value))?;
+                    res.jobs = Some(jobs);
+                }
+                "-f" | "--format" => res.format = value()?.parse()?,
+                "--check" => res.check = true,
+                "--visualize" => res.visualize = true,
+                "--trace" => res.trace = true,
+                "--answers-dir" => res.answers_dir = Some(value()?.into()),
+                _ => bail!("Unknown argument '{}'", flag),
+            }
+        }
+
+        Ok(res)
+    }
+
+    /// Resolves the arguments against the solutions available in `registry`.
+    pub fn into_config(self, registry: &Registry) -> Result<Config> {
+        let Args {
+            selection,
+            mut inputs,
+            input,
+            check,
+            answers_dir,
+            bench,
+            jobs,
+            visualize,
+            format,
+            ..
+        } = self;
+        selection.validate(&registry.days())?;
+        if visualize && format != Format::Text {
+            bail!("'--visualize' only works with the text format");
+        }
+
+        if let Some(input) = input {
+            let day = match &selection.days {
+                Days::Last => registry.last().map(|s| s.metadata().day),
+                Days::Only(days) if days.len() == 1 => days.first().copied(),
+                _ => None,
+            };
+            let Some(day) = day else {
+                bail!("'--input' without a day requires exactly one selected day, use `--input DAY=PATH`");
+            };
+            inputs.set(day, input);
+        }
+
+        let last_day = registry.last().map_or(0, |s| s.metadata().day);
+        let selected = |day| registry.get(day).is_some() && selection.includes_day(day, last_day);
+        if let Some((day, _)) = inputs.overrides().find(|(day, _)| !selected(*day)) {
+            bail!("'--input {}=...' is given but day {} is not selected", day, day);
+        }
+        // Stdin can only be read once, every other day reading from it would get an empty input.
+        let stdin_days = inputs
+            .overrides()
+            .filter(|(_, source)| **source == InputSource::Stdin)
+            .count();
+        if stdin_days > 1 {
+            bail!("Only one day can read its input from stdin");
+        }
+
+        let mut config = Config {
+            selection,
+            inputs,
+            check,
+            bench,
+            visualize,
+            format,
+            ..Default::default()
+        };
+        if let Some(answers_dir) = answers_dir {
+            config.answers_dir = answers_dir;
+        }
+        if let Some(jobs) = jobs {
+            config.jobs = jobs;
+        }
+        Ok(config)
+    }
+
+    fn select_days(&mut self, days: Days) -> Result<()> {
+        self.selection.days = match (std::mem::take(&mut self.selection.days), days) {
+            (Days::Last, days) => days,
+            (Days::All, Days::All) => Days::All,
+            (Days::Only(mut current), Days::Only(days)) => {
+                current.extend(days);
+                Days::Only(current)
+            }
+            _ => bail!("'--all' cannot be combined with '--day'"),
+        };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(args: &str) -> Result<Config> {
+        Args::parse(args.split_whitespace().map(str::to_string))?.into_config(crate::registry())
+    }
+
+    #[test]
+    fn inputs_for_selected_days() {
+        let config = config("-d 1,2 --input 1=- --input 2=input/1").unwrap();
+        assert_eq!(config.inputs.source(1), InputSource::Stdin);
+        assert_eq!(config.inputs.source(2), "input/1".parse().unwrap());
+        assert_eq!(config.inputs.source(3), "input/3".parse().unwrap());
+        assert!(self::config("-d 2 --input -").is_ok());
+        assert!(self::config("-a --input 2=-").is_ok());
+    }
+
+    #[test]
+    fn inputs_for_unselected_days() {
+        assert!(config("-d 1 --input 2=input/2").is_err());
+        assert!(config("--input 1=input/1").is_err());
+        assert!(config("-a --input 99=input/1").is_err());
+    }
+
+    #[test]
+    fn stdin_is_read_by_one_day_only() {
+        assert!(config("-d 1,2 --input 1=- --input 2=-").is_err());
+        assert!(config("-d 1,2 --input - --input 2=-").is_err());
+    }
+}
@@ -0,0 +1,183 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+};
+
+/// Where the states reached by repeating a step function start to repeat.
+///
+/// The detectors never return for a sequence of states that does not cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// Number of steps before the first state that is part of the cycle.
+    pub mu: usize,
+    /// Number of steps after which the states repeat.
+    pub lambda: usize,
+}
+
+impl Cycle {
+    /// The first step holding the same state as `step`, at most `mu + lambda - 1`.
+    pub fn equivalent_step(&self, step: usize) -> usize {
+        if step < self.mu {
+            step
+        } else {
+            self.mu + (step - self.mu) % self.lambda
+        }
+    }
+
+    /// State after `target` steps from `start`, taking at most `mu + lambda - 1` steps.
+    pub fn state_at<S>(&self, start: S, mut step: impl FnMut(&S) -> S, target: usize) -> S {
+        (0..self.equivalent_step(target)).fold(start, |state, _| step(&state))
+    }
+}
+
+/// Floyd's tortoise and hare, keeps two states around at a time.
+pub fn floyd<S: Eq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    // The distance between both is now a multiple of lambda, so they meet again at the start of the cycle.
+    let mut mu = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        mu += 1;
+    }
+
+    let mut lambda = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        lambda += 1;
+    }
+    Cycle { mu, lambda }
+}
+
+/// Brent's algorithm, keeps two states around at a time and takes fewer steps than [`floyd`].
+pub fn brent<S: Clone + Eq>(start: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut lambda = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == lambda {
+            tortoise = hare.clone();
+            power *= 2;
+            lambda = 0;
+        }
+        hare = step(&hare);
+        lambda += 1;
+    }
+
+    // Move the hare lambda steps ahead, both then meet at the start of the cycle.
+    let mut tortoise = start.clone();
+    let mut hare = (0..lambda).fold(start, |state, _| step(&state));
+    let mut mu = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        mu += 1;
+    }
+    Cycle { mu, lambda }
+}
+
+/// Remembers every state until one repeats, takes the fewest steps but keeps `mu + lambda` states around.
+pub fn hashed<S: Clone + Eq + Hash>(start: S, step: impl FnMut(&S) -> S) -> Cycle {
+    hashed_by(start, step, S::clone)
+}
+
+/// Like [`hashed`] but remembers the `key` of every state instead, states with the same key are taken to be the same.
+pub fn hashed_by<S, K: Eq + Hash>(start: S, mut step: impl FnMut(&S) -> S, mut key: impl FnMut(&S) -> K) -> Cycle {
+    let mut seen = HashMap::new();
+    let mut state = start;
+    let mut idx = 0;
+    loop {
+        match seen.entry(key(&state)) {
+            Entry::Occupied(entry) => {
+                let mu = *entry.get();
+                return Cycle { mu, lambda: idx - mu };
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(idx);
+                state = step(&state);
+                idx += 1;
+            }
+        }
+    }
+}
+
+/// State after `target` steps from `start`, remembering the `key` of every state and skipping whole cycles once one
+/// repeats, never takes more than `target` steps.
+pub fn hashed_state_at<S, K: Eq + Hash>(
+    start: S,
+    mut step: impl FnMut(&S) -> S,
+    mut key: impl FnMut(&S) -> K,
+    target: usize,
+) -> S {
+    let mut seen = HashMap::new();
+    let mut state = start;
+    for idx in 0..target {
+        if let Some(mu) = seen.insert(key(&state), idx) {
+            let remaining = (target - idx) % (idx - mu);
+            return (0..remaining).fold(state, |state, _| step(&state));
+        }
+        state = step(&state);
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts up to 9 then jumps back to 4.
+    fn step(n: &u32) -> u32 {
+        if *n == 9 {
+            4
+        } else {
+            n + 1
+        }
+    }
+
+    #[test]
+    fn detectors_agree() {
+        let expected = Cycle { mu: 4, lambda: 6 };
+        assert_eq!(floyd(0, step), expected);
+        assert_eq!(brent(0, step), expected);
+        assert_eq!(hashed(0, step), expected);
+        assert_eq!(hashed(7, step), Cycle { mu: 0, lambda: 6 });
+        assert_eq!(hashed_by(0, step, |n| n % 10), expected);
+        assert_eq!(brent(3, |_| 3), Cycle { mu: 0, lambda: 1 });
+    }
+
+    #[test]
+    fn state_at_target_step() {
+        let cycle = brent(0, step);
+        assert_eq!(cycle.state_at(0, step, 3), 3);
+        assert_eq!(cycle.state_at(0, step, 10), 4);
+        assert_eq!(cycle.state_at(0, step, 1_000_000_000), 4);
+    }
+
+    #[test]
+    fn hashed_state_at_stops_at_target() {
+        for target in [0, 3, 10, 11, 1_000_000_000] {
+            let mut steps = 0;
+            let state = hashed_state_at(
+                0,
+                |n| {
+                    steps += 1;
+                    step(n)
+                },
+                |n| *n,
+                target,
+            );
+            assert_eq!(state, brent(0, step).state_at(0, step, target));
+            assert!(steps <= target);
+        }
+    }
+}
@@ -1,8 +1,36 @@
 use anyhow::{bail, Result};
 
-pub fn part1(input: &str) -> Result<i32> {
+use crate::{
+    answer::Answer,
+    solution::{Metadata, Solution},
+};
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<String>;
+
+    const METADATA: Metadata = Metadata {
+        day: 1,
+        title: "Trebuchet?!",
+    };
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.lines().map(str::to_string).collect())
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+}
+
+pub fn part1(input: &[String]) -> Result<i32> {
     input
-        .lines()
+        .iter()
         .map(|val| -> Result<i32> {
             let mut res = Res::default();
             for ele in val.chars() {
@@ -18,9 +46,9 @@ pub fn part1(input: &str) -> Result<i32> {
         .sum::<Result<i32>>()
 }
 
-pub fn part2(input: &str) -> Result<i32> {
+pub fn part2(input: &[String]) -> Result<i32> {
     input
-        .lines()
+        .iter()
         .map(|val| -> Result<i32> {
             let mut res = Res::default();
             let chars: Vec<char> = val.chars().collect();
@@ -87,3 +115,27 @@ fn find_num_from_word_of_len(chars: &[char], idx: usize) -> Option<u32> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE: &str = "\
+1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet
+";
+    const SAMPLE_WORDS: &str = "\
+two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen
+";
+
+    crate::sample_tests!(super::Day1 {
+        part1_sample: part1(SAMPLE) == 142,
+        part2_sample: part2(SAMPLE_WORDS) == 281,
+    });
+}
@@ -1,345 +1,336 @@
-use std::{collections::VecDeque, str::FromStr};
+use std::{cell::OnceCell, collections::HashSet, io::Write, str::FromStr};
 
-use anyhow::{anyhow, Result};
+use anyhow::Result;
+use colored::Colorize;
 use itertools::Itertools;
 
-pub fn part1(input: &str) -> Result<i32> {
-    let map = input.parse::<Map>()?;
+use crate::{
+    answer::Answer,
+    grid::{Coord, Direction, Grid},
+    search,
+    solution::{Metadata, Part, Solution},
+};
 
-    let mut len = 1;
-    let [mut first, mut second] = map.find_connections_from(&map.start);
-    let [mut first_prev, mut second_prev] = [map.start, map.start];
-    while first != second {
-        let next_first = map.next(&first, &first_prev).ok_or_else(|| anyhow!("no next"))?;
-        let next_second = map.next(&second, &second_prev).ok_or_else(|| anyhow!("no next"))?;
+pub struct Day10;
 
-        first_prev = first;
-        second_prev = second;
+impl Solution for Day10 {
+    type Input = Map;
 
-        first = next_first;
-        second = next_second;
+    const METADATA: Metadata = Metadata {
+        day: 10,
+        title: "Pipe Maze",
+    };
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.parse()?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
 
-        len += 1;
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
     }
 
-    Ok(len)
+    fn visualize(input: &Self::Input, part: Part, out: &mut dyn Write) -> Result<bool> {
+        let pipe_loop = input.trace_loop()?;
+        let enclosed = match part {
+            Part::One => {
+                let (farthest, steps) = pipe_loop.farthest();
+                writeln!(
+                    out,
+                    "Loop of {} tiles, {} is {} steps away",
+                    pipe_loop.len(),
+                    farthest,
+                    steps
+                )?;
+                None
+            }
+            Part::Two => {
+                writeln!(
+                    out,
+                    "{} tiles enclosed by a loop running {:?}",
+                    pipe_loop.enclosed().len(),
+                    pipe_loop.orientation()
+                )?;
+                Some(pipe_loop.enclosed())
+            }
+        };
+        let colored = colored::control::SHOULD_COLORIZE.should_colorize();
+        writeln!(out, "{}", input.render(&pipe_loop, enclosed, colored))?;
+        Ok(true)
+    }
 }
 
-pub fn part2(input: &str) -> Result<i32> {
-    let mut map = input.parse::<Map>()?;
+pub fn part1(map: &Map) -> Result<usize> {
+    Ok(map.trace_loop()?.farthest().1)
+}
 
-    let start = map.start;
-    let [mut first, mut second] = map.find_connections_from(&start);
-    map.mark_as_loop(&first);
-    map.mark_as_loop(&second);
-    map.mark_as_loop(&start);
-    let [mut first_prev, mut second_prev] = [start, start];
-    while first != second {
-        let next_first = map.next(&first, &first_prev).ok_or_else(|| anyhow!("no next"))?;
-        let next_second = map.next(&second, &second_prev).ok_or_else(|| anyhow!("no next"))?;
+pub fn part2(map: &Map) -> Result<usize> {
+    Ok(map.trace_loop()?.enclosed().len())
+}
 
-        first_prev = first;
-        second_prev = second;
+/// Direction the loop runs in as drawn, with rows going down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+}
 
-        first = next_first;
-        second = next_second;
+/// The loop of pipes going through the start tile.
+#[derive(Debug, Clone)]
+pub struct PipeLoop<'a> {
+    map: &'a Map,
+    tiles: Vec<Coord>,
+    start_pipe: Pipe,
+    /// Only found when asked for, it takes flooding a map nine times the size.
+    enclosed: OnceCell<Vec<Coord>>,
+}
 
-        map.mark_as_loop(&first);
-        map.mark_as_loop(&second);
+impl PipeLoop<'_> {
+    /// Tiles in the order the loop is walked, starting with the start tile.
+    pub fn tiles(&self) -> &[Coord] {
+        &self.tiles
     }
 
-    for row in map.map.iter_mut() {
-        for pipe in row.iter_mut() {
-            if !pipe.is_part_of_loop() {
-                *pipe = Pipe::Ground(false);
-            }
-        }
+    pub fn len(&self) -> usize {
+        self.tiles.len()
     }
 
-    let mut expanded = Vec::new();
-    for y in 0..map.map.len() {
-        let mut row = Vec::new();
-        let mut row2 = Vec::new();
-        let mut row3 = Vec::new();
-        for x in 0..map.map[y].len() {
-            match map[Coord { x, y }] {
-                Pipe::NorthToSouth(is_pipe) => {
-                    row.push(Pipe::Ground(false));
-                    row.push(Pipe::NorthToSouth(is_pipe));
-                    row.push(Pipe::Ground(false));
-
-                    row2.push(Pipe::Ground(false));
-                    row2.push(Pipe::NorthToSouth(is_pipe));
-                    row2.push(Pipe::Ground(false));
-
-                    row3.push(Pipe::Ground(false));
-                    row3.push(Pipe::NorthToSouth(is_pipe));
-                    row3.push(Pipe::Ground(false));
-                }
-                Pipe::EastToWest(is_pipe) => {
-                    row.push(Pipe::Ground(false));
-                    row.push(Pipe::Ground(false));
-                    row.push(Pipe::Ground(false));
-
-                    row2.push(Pipe::EastToWest(is_pipe));
-                    row2.push(Pipe::EastToWest(is_pipe));
-                    row2.push(Pipe::EastToWest(is_pipe));
-
-                    row3.push(Pipe::Ground(false));
-                    row3.push(Pipe::Ground(false));
-                    row3.push(Pipe::Ground(false));
-                }
-                Pipe::NorthToEast(is_pipe) => {
-                    row.push(Pipe::Ground(false));
-                    row.push(Pipe::NorthToSouth(is_pipe));
-                    row.push(Pipe::Ground(false));
-
-                    row2.push(Pipe::Ground(false));
-                    row2.push(Pipe::NorthToEast(is_pipe));
-                    row2.push(Pipe::EastToWest(is_pipe));
-
-                    row3.push(Pipe::Ground(false));
-                    row3.push(Pipe::Ground(false));
-                    row3.push(Pipe::Ground(false));
-                }
-                Pipe::NorthToWest(is_pipe) => {
-                    row.push(Pipe::Ground(false));
-                    row.push(Pipe::NorthToSouth(is_pipe));
-                    row.push(Pipe::Ground(false));
-
-                    row2.push(Pipe::EastToWest(is_pipe));
-                    row2.push(Pipe::NorthToWest(is_pipe));
-                    row2.push(Pipe::Ground(false));
-
-                    row3.push(Pipe::Ground(false));
-                    row3.push(Pipe::Ground(false));
-                    row3.push(Pipe::Ground(false));
-                }
-                Pipe::SouthToEast(is_pipe) => {
-                    row.push(Pipe::Ground(false));
-                    row.push(Pipe::Ground(false));
-                    row.push(Pipe::Ground(false));
-
-                    row2.push(Pipe::Ground(false));
-                    row2.push(Pipe::SouthToEast(is_pipe));
-                    row2.push(Pipe::EastToWest(is_pipe));
-
-                    row3.push(Pipe::Ground(false));
-                    row3.push(Pipe::NorthToSouth(is_pipe));
-                    row3.push(Pipe::Ground(false));
-                }
-                Pipe::SouthToWest(is_pipe) => {
-                    row.push(Pipe::Ground(false));
-                    row.push(Pipe::Ground(false));
-                    row.push(Pipe::Ground(false));
-
-                    row2.push(Pipe::EastToWest(is_pipe));
-                    row2.push(Pipe::SouthToWest(is_pipe));
-                    row2.push(Pipe::Ground(false));
-
-                    row3.push(Pipe::Ground(false));
-                    row3.push(Pipe::NorthToSouth(is_pipe));
-                    row3.push(Pipe::Ground(false));
-                }
-                Pipe::Ground(_) => {
-                    row.push(Pipe::Ground(false));
-                    row.push(Pipe::Ground(false));
-                    row.push(Pipe::Ground(false));
-
-                    row2.push(Pipe::Ground(false));
-                    row2.push(Pipe::Ground(false));
-                    row2.push(Pipe::Ground(false));
-
-                    row3.push(Pipe::Ground(false));
-                    row3.push(Pipe::Ground(false));
-                    row3.push(Pipe::Ground(false));
-                }
-                Pipe::Start => {
-                    let start = Coord { x, y };
-                    let [first, second] = map.find_connections_from(&Coord { x, y });
-
-                    row.push(Pipe::Ground(false));
-                    row.push(Pipe::Ground(false));
-                    row.push(Pipe::Ground(false));
-
-                    row2.push(Pipe::Ground(false));
-                    row2.push(Pipe::Start);
-                    row2.push(Pipe::Ground(false));
-
-                    row3.push(Pipe::Ground(false));
-                    row3.push(Pipe::Ground(false));
-                    row3.push(Pipe::Ground(false));
-
-                    let rlen = row.len();
-                    let r2len = row2.len();
-                    let r3len = row3.len();
-
-                    if first.x == start.x && first.y > start.y {
-                        row3[r3len - 2] = Pipe::Start;
-                    } else if first.x == start.x && first.y < start.y {
-                        row[rlen - 2] = Pipe::Start;
-                    } else if first.y == start.y && first.x > start.x {
-                        row2[r2len - 1] = Pipe::Start;
-                    } else if first.y == start.y && first.x < start.x {
-                        row2[r2len - 3] = Pipe::Start;
-                    }
-                    if second.x == start.x && second.y > start.y {
-                        row3[r3len - 2] = Pipe::Start;
-                    } else if second.x == start.x && second.y < start.y {
-                        row[rlen - 2] = Pipe::Start;
-                    } else if second.y == start.y && second.x > start.x {
-                        row2[r2len - 1] = Pipe::Start;
-                    } else if second.y == start.y && second.x < start.x {
-                        row2[r2len - 3] = Pipe::Start;
-                    }
-                }
-            };
-        }
-        expanded.push(row);
-        expanded.push(row2);
-        expanded.push(row3);
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
     }
 
-    let mut exmap = Map {
-        start: Coord {
-            x: start.x * 3,
-            y: start.y * 3,
-        },
-        map: expanded.clone(),
-    };
+    pub fn start(&self) -> Coord {
+        self.tiles[0]
+    }
 
-    fill(Coord { x: 70 * 3, y: 70 * 3 }, &mut exmap);
-
-    let mut count = 0;
-    for y in 0..map.map.len() {
-        for x in 0..map.map[y].len() {
-            if exmap[Coord {
-                x: x * 3 + 1,
-                y: y * 3 + 1,
-            }] == Pipe::Ground(true)
-            {
-                count += 1;
-            }
-        }
+    /// Pipe hidden under the start tile.
+    pub fn start_pipe(&self) -> Pipe {
+        self.start_pipe
     }
 
-    Ok(count)
-}
+    /// Tile farthest from the start following the loop either way, and the number of steps to it.
+    pub fn farthest(&self) -> (Coord, usize) {
+        let steps = self.len() / 2;
+        (self.tiles[steps], steps)
+    }
 
-fn fill(node: Coord, map: &mut Map) {
-    let mut stack = VecDeque::new();
-    stack.push_back(node);
-    while !stack.is_empty() {
-        let current = stack.pop_front().unwrap();
-        if map[current] == Pipe::Ground(true) {
-            continue;
-        }
-        map[current] = Pipe::Ground(true);
+    /// Tiles inside of the loop, row by row.
+    pub fn enclosed(&self) -> &[Coord] {
+        self.enclosed.get_or_init(|| {
+            let expanded = self.map.filled_expansion(self);
+            self.map
+                .map
+                .coords()
+                .filter(|Coord { x, y }| expanded[Coord::new(x * 3 + 1, y * 3 + 1)] == Pipe::Ground(false))
+                .collect()
+        })
+    }
 
-        if current.up().is_some_and(|up| map[up] == Pipe::Ground(false)) {
-            stack.push_back(current.up().unwrap());
-        }
-        if current.down(map).is_some_and(|down| map[down] == Pipe::Ground(false)) {
-            stack.push_back(current.down(map).unwrap());
-        }
-        if current.left().is_some_and(|left| map[left] == Pipe::Ground(false)) {
-            stack.push_back(current.left().unwrap());
-        }
-        if current
-            .right(map)
-            .is_some_and(|right| map[right] == Pipe::Ground(false))
-        {
-            stack.push_back(current.right(map).unwrap());
+    pub fn orientation(&self) -> Orientation {
+        // Twice the signed area by the shoelace formula, positive when running clockwise with rows going down.
+        let area: isize = self
+            .tiles
+            .iter()
+            .circular_tuple_windows()
+            .map(|(a, b)| (a.x * b.y) as isize - (b.x * a.y) as isize)
+            .sum();
+        if area > 0 {
+            Orientation::Clockwise
+        } else {
+            Orientation::CounterClockwise
         }
     }
 }
 
+fn fill(node: Coord, map: &mut Map) {
+    let filled = search::reachable([node], |current| {
+        map.map
+            .neighbours4(*current)
+            .filter(|(_, neighbour)| map[*neighbour] == Pipe::Ground(false))
+            .map(|(_, neighbour)| neighbour)
+            .collect_vec()
+    });
+    for coord in filled.into_keys() {
+        map[coord] = Pipe::Ground(true);
+    }
+}
+
+/// Where a tile lies relative to the loop.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Coord {
-    x: usize,
-    y: usize,
+enum Region {
+    Loop,
+    Inside,
+    Outside,
+    /// Off the loop, without knowing which side.
+    Unknown,
 }
 
-#[derive(Debug)]
-struct Map {
+/// Direction of a neighbouring tile.
+fn direction(from: Coord, to: Coord) -> Direction {
+    if to.y < from.y {
+        Direction::Up
+    } else if to.x > from.x {
+        Direction::Right
+    } else if to.y > from.y {
+        Direction::Down
+    } else {
+        Direction::Left
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Map {
     start: Coord,
-    map: Vec<Vec<Pipe>>,
+    map: Grid<Pipe>,
 }
 
 impl Map {
-    fn next(&self, current: &Coord, previous: &Coord) -> Option<Coord> {
-        if current.x > previous.x {
-            match self[*current] {
-                Pipe::EastToWest(_) => current.right(self),
-                Pipe::NorthToWest(_) => current.up(),
-                Pipe::SouthToWest(_) => current.down(self),
-                _ => None,
-            }
-        } else if current.x < previous.x {
-            match self[*current] {
-                Pipe::EastToWest(_) => current.left(),
-                Pipe::NorthToEast(_) => current.up(),
-                Pipe::SouthToEast(_) => current.down(self),
-                _ => None,
+    /// Walks the loop from the start tile back to it.
+    pub fn trace_loop(&self) -> Result<PipeLoop<'_>, MapError> {
+        let start = self.start;
+        let first = match self.connections_to(start)[..] {
+            [first, _] => first,
+            [] | [_] => return Err(MapError::DeadEnd { coord: start }),
+            ref connections => {
+                return Err(MapError::BranchingLoop {
+                    coord: start,
+                    connections: connections.len(),
+                })
             }
-        } else if current.y > previous.y {
-            match self[*current] {
-                Pipe::NorthToSouth(_) => current.down(self),
-                Pipe::NorthToEast(_) => current.right(self),
-                Pipe::NorthToWest(_) => current.left(),
-                _ => None,
-            }
-        } else if current.y < previous.y {
-            match self[*current] {
-                Pipe::NorthToSouth(_) => current.up(),
-                Pipe::SouthToEast(_) => current.right(self),
-                Pipe::SouthToWest(_) => current.left(),
-                _ => None,
-            }
-        } else {
-            None
+        };
+        let mut tiles = vec![start];
+        let (mut previous, mut current) = (start, first);
+        while current != start {
+            tiles.push(current);
+            let next = self.next(current, previous)?;
+            previous = current;
+            current = next;
         }
+
+        Ok(PipeLoop {
+            map: self,
+            tiles,
+            start_pipe: Pipe::connecting(direction(start, first), direction(start, previous)),
+            enclosed: OnceCell::new(),
+        })
     }
 
-    fn find_connections_from(&self, coord: &Coord) -> [Coord; 2] {
-        let mut connections = [Coord { x: 0, y: 0 }, Coord { x: 0, y: 0 }];
-        let mut found = 0;
-        if let Some(left) = coord.left() {
-            match self[left] {
-                Pipe::EastToWest(_) | Pipe::NorthToEast(_) | Pipe::SouthToEast(_) => {
-                    connections[found] = left;
-                    found += 1;
-                }
-                _ => {}
-            };
+    /// The [`expanded`](Map::expanded) map holding only the loop, with the ground outside of it marked as filled.
+    pub fn filled_expansion(&self, pipe_loop: &PipeLoop) -> Map {
+        let mut loop_only = self.clone();
+        for tile in pipe_loop.tiles() {
+            loop_only.mark_as_loop(tile);
         }
-        if let Some(right) = coord.right(self) {
-            match self[right] {
-                Pipe::EastToWest(_) | Pipe::NorthToWest(_) | Pipe::SouthToWest(_) => {
-                    connections[found] = right;
-                    found += 1;
-                }
-                _ => {}
-            };
+        for pipe in loop_only.map.values_mut() {
+            if !pipe.is_part_of_loop() {
+                *pipe = Pipe::Ground(false);
+            }
         }
-        if let Some(up) = coord.up() {
-            match self[up] {
-                Pipe::SouthToEast(_) | Pipe::SouthToWest(_) | Pipe::NorthToSouth(_) => {
-                    connections[found] = up;
-                    found += 1;
-                }
-                _ => {}
+        loop_only[pipe_loop.start()] = pipe_loop.start_pipe();
+
+        let mut expanded = loop_only.expanded();
+        // Pipes only run through the middle of their 3x3 block and the loop cannot leave the map, so the outer ring of
+        // the expanded map is ground connected to everything outside of the loop.
+        fill(Coord { x: 0, y: 0 }, &mut expanded);
+        expanded
+    }
+
+    /// Draws the map with box drawing characters, colored for a terminal when `colored`.
+    ///
+    /// The loop is highlighted and other pipes are dimmed, the `enclosed` tiles are shaded when given. Without colors
+    /// ground is then drawn as `I` inside of the loop and as `O` outside of it.
+    pub fn render(&self, pipe_loop: &PipeLoop, enclosed: Option<&[Coord]>, colored: bool) -> String {
+        let tiles = pipe_loop.tiles().iter().collect::<HashSet<_>>();
+        let enclosed = enclosed.map(|enclosed| enclosed.iter().collect::<HashSet<_>>());
+        self.render_with(colored, |coord, _| match &enclosed {
+            _ if tiles.contains(&coord) => Region::Loop,
+            Some(enclosed) if enclosed.contains(&coord) => Region::Inside,
+            Some(_) => Region::Outside,
+            None => Region::Unknown,
+        })
+    }
+
+    /// Draws the [`filled_expansion`](Map::filled_expansion) of the map like [`render`](Map::render).
+    pub fn render_expanded(&self, pipe_loop: &PipeLoop, colored: bool) -> String {
+        self.filled_expansion(pipe_loop)
+            .render_with(colored, |_, pipe| match pipe {
+                Pipe::Ground(true) => Region::Outside,
+                Pipe::Ground(false) => Region::Inside,
+                _ => Region::Loop,
+            })
+    }
+
+    fn render_with(&self, colored: bool, region: impl Fn(Coord, Pipe) -> Region) -> String {
+        let mut res = String::new();
+        for (coord, pipe) in self.map.iter() {
+            if coord.x == 0 && coord.y > 0 {
+                res.push('\n');
+            }
+            let region = region(coord, *pipe);
+            let is_ground = matches!(pipe, Pipe::Ground(_));
+            let cell = match (region, is_ground, colored) {
+                (Region::Loop, _, false) => pipe.to_string(),
+                (Region::Loop, _, true) => pipe.to_string().bright_yellow().bold().to_string(),
+                (Region::Inside, true, false) => "I".to_string(),
+                (Region::Outside, true, false) => "O".to_string(),
+                (_, _, false) => pipe.to_string(),
+                (Region::Inside, true, true) => " ".on_blue().to_string(),
+                (Region::Inside, false, true) => pipe.to_string().dimmed().on_blue().to_string(),
+                (Region::Outside | Region::Unknown, true, true) => " ".to_string(),
+                (Region::Outside | Region::Unknown, false, true) => pipe.to_string().dimmed().to_string(),
             };
+            res.push_str(&cell);
         }
-        if let Some(down) = coord.down(self) {
-            match self[down] {
-                Pipe::NorthToEast(_) | Pipe::NorthToWest(_) | Pipe::NorthToSouth(_) => {
-                    connections[found] = down;
-                }
-                _ => {}
-            };
+        res
+    }
+
+    /// Map three times the size where every tile becomes a 3x3 block, so ground squeezed between pipes gets a path
+    /// through. The start tile needs to be replaced by its pipe first.
+    pub fn expanded(&self) -> Map {
+        let mut map = Grid::new(self.map.width() * 3, self.map.height() * 3, Pipe::Ground(false));
+        for (coord, pipe) in self.map.iter() {
+            let center = Coord::new(coord.x * 3 + 1, coord.y * 3 + 1);
+            map[center] = *pipe;
+            let is_loop = pipe.is_part_of_loop();
+            for direction in Direction::ALL.into_iter().filter(|d| pipe.connects(*d)) {
+                let neighbour = center.step(direction).expect("The center of a block has neighbours");
+                map[neighbour] = if direction.is_horizontal() {
+                    Pipe::EastToWest(is_loop)
+                } else {
+                    Pipe::NorthToSouth(is_loop)
+                };
+            }
+        }
+        Map {
+            start: Coord::new(self.start.x * 3 + 1, self.start.y * 3 + 1),
+            map,
         }
-        connections
+    }
+
+    /// Tile after `current` when coming from `previous`, which needs to connect back to `current`.
+    fn next(&self, current: Coord, previous: Coord) -> Result<Coord, MapError> {
+        let back = direction(current, previous);
+        let pipe = self[current];
+        Direction::ALL
+            .into_iter()
+            .filter(|direction| *direction != back && pipe.connects(*direction))
+            .find_map(|direction| {
+                let next = self.map.neighbour(current, direction)?;
+                (next == self.start || self[next].connects(direction.opposite())).then_some(next)
+            })
+            .ok_or(MapError::DeadEnd { coord: current })
+    }
+
+    /// Neighbours with a pipe connecting to `coord`.
+    fn connections_to(&self, coord: Coord) -> Vec<Coord> {
+        self.map
+            .neighbours4(coord)
+            .filter(|(direction, neighbour)| self[*neighbour].connects(direction.opposite()))
+            .map(|(_, neighbour)| neighbour)
+            .collect()
     }
 
     fn mark_as_loop(&mut self, coord: &Coord) {
@@ -360,51 +351,111 @@ impl std::ops::Index<Coord> for Map {
     type Output = Pipe;
 
     fn index(&self, index: Coord) -> &Self::Output {
-        &self.map[index.y][index.x]
+        &self.map[index]
     }
 }
 
 impl std::ops::IndexMut<Coord> for Map {
     fn index_mut(&mut self, index: Coord) -> &mut Self::Output {
-        &mut self.map[index.y][index.x]
+        &mut self.map[index]
     }
 }
 
 impl FromStr for Map {
-    type Err = anyhow::Error;
+    type Err = MapError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut start = Coord { x: 0, y: 0 };
-        let map = s
-            .lines()
-            .enumerate()
-            .map(|(y, line)| {
-                line.chars()
-                    .enumerate()
-                    .map(|(x, c)| match c {
-                        '|' => Pipe::NorthToSouth(false),
-                        '-' => Pipe::EastToWest(false),
-                        'L' => Pipe::NorthToEast(false),
-                        'F' => Pipe::SouthToEast(false),
-                        'J' => Pipe::NorthToWest(false),
-                        '7' => Pipe::SouthToWest(false),
-                        '.' => Pipe::Ground(false),
-                        'S' => {
-                            start.x = x;
-                            start.y = y;
+        let mut start = None;
+        let mut rows = Vec::new();
+        for (y, line) in s.lines().enumerate() {
+            let mut row = Vec::new();
+            for (x, c) in line.chars().enumerate() {
+                let coord = Coord { x, y };
+                row.push(match c {
+                    '|' => Pipe::NorthToSouth(false),
+                    '-' => Pipe::EastToWest(false),
+                    'L' => Pipe::NorthToEast(false),
+                    'F' => Pipe::SouthToEast(false),
+                    'J' => Pipe::NorthToWest(false),
+                    '7' => Pipe::SouthToWest(false),
+                    '.' => Pipe::Ground(false),
+                    'S' => match start {
+                        None => {
+                            start = Some(coord);
                             Pipe::Start
                         }
-                        _ => unreachable!(),
-                    })
-                    .collect_vec()
-            })
-            .collect_vec();
-        Ok(Map { map, start })
+                        Some(first) => return Err(MapError::MultipleStarts { first, second: coord }),
+                    },
+                    c => return Err(MapError::InvalidTile { coord, c }),
+                });
+            }
+            if let Some(expected) = rows.first().map(Vec::len).filter(|expected| *expected != row.len()) {
+                return Err(MapError::RaggedRow {
+                    coord: Coord { x: row.len(), y },
+                    expected,
+                });
+            }
+            rows.push(row);
+        }
+
+        Ok(Map {
+            start: start.ok_or(MapError::MissingStart)?,
+            map: Grid::from_rows(rows).expect("Rows have the same length"),
+        })
+    }
+}
+
+/// Why a map cannot be parsed or its loop cannot be traced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapError {
+    InvalidTile {
+        coord: Coord,
+        c: char,
+    },
+    RaggedRow {
+        /// Just past the end of the row, so `x` is its width.
+        coord: Coord,
+        expected: usize,
+    },
+    MissingStart,
+    MultipleStarts {
+        first: Coord,
+        second: Coord,
+    },
+    /// The loop cannot continue from the pipe at `coord`.
+    DeadEnd {
+        coord: Coord,
+    },
+    /// More than two pipes connect to the start tile at `coord`.
+    BranchingLoop {
+        coord: Coord,
+        connections: usize,
+    },
+}
+
+impl std::fmt::Display for MapError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MapError::InvalidTile { coord, c } => write!(f, "Invalid pipe '{}' at {}", c, coord),
+            MapError::RaggedRow { coord, expected } => {
+                write!(f, "Row {} has {} tiles, expected {}", coord.y, coord.x, expected)
+            }
+            MapError::MissingStart => write!(f, "The map has no start tile"),
+            MapError::MultipleStarts { first, second } => {
+                write!(f, "The map has start tiles at {} and {}", first, second)
+            }
+            MapError::DeadEnd { coord } => write!(f, "The loop ends at {}", coord),
+            MapError::BranchingLoop { coord, connections } => {
+                write!(f, "The loop branches into {} pipes at {}", connections, coord)
+            }
+        }
     }
 }
 
+impl std::error::Error for MapError {}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Pipe {
+pub enum Pipe {
     NorthToSouth(bool),
     EastToWest(bool),
     NorthToEast(bool),
@@ -416,6 +467,31 @@ enum Pipe {
 }
 
 impl Pipe {
+    /// Pipe on the loop connecting both directions.
+    fn connecting(a: Direction, b: Direction) -> Pipe {
+        let has = |direction| a == direction || b == direction;
+        match (has(Direction::Up), has(Direction::Right), has(Direction::Down)) {
+            (true, _, true) => Pipe::NorthToSouth(true),
+            (true, true, _) => Pipe::NorthToEast(true),
+            (true, _, _) => Pipe::NorthToWest(true),
+            (_, true, true) => Pipe::SouthToEast(true),
+            (_, true, _) => Pipe::EastToWest(true),
+            _ => Pipe::SouthToWest(true),
+        }
+    }
+
+    pub fn connects(&self, direction: Direction) -> bool {
+        match self {
+            Pipe::NorthToSouth(_) => !direction.is_horizontal(),
+            Pipe::EastToWest(_) => direction.is_horizontal(),
+            Pipe::NorthToEast(_) => matches!(direction, Direction::Up | Direction::Right),
+            Pipe::NorthToWest(_) => matches!(direction, Direction::Up | Direction::Left),
+            Pipe::SouthToEast(_) => matches!(direction, Direction::Down | Direction::Right),
+            Pipe::SouthToWest(_) => matches!(direction, Direction::Down | Direction::Left),
+            Pipe::Ground(_) | Pipe::Start => false,
+        }
+    }
+
     fn is_part_of_loop(&self) -> bool {
         matches!(
             self,
@@ -433,12 +509,12 @@ impl Pipe {
 impl std::fmt::Display for Pipe {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Pipe::NorthToSouth(_) => write!(f, "┃"),
-            Pipe::EastToWest(_) => write!(f, "━"),
-            Pipe::NorthToEast(_) => write!(f, "┗"),
-            Pipe::NorthToWest(_) => write!(f, "┛"),
-            Pipe::SouthToEast(_) => write!(f, "┏"),
-            Pipe::SouthToWest(_) => write!(f, "┓"),
+            Pipe::NorthToSouth(_) => write!(f, "│"),
+            Pipe::EastToWest(_) => write!(f, "─"),
+            Pipe::NorthToEast(_) => write!(f, "└"),
+            Pipe::NorthToWest(_) => write!(f, "┘"),
+            Pipe::SouthToEast(_) => write!(f, "┌"),
+            Pipe::SouthToWest(_) => write!(f, "┐"),
             Pipe::Ground(true) => write!(f, "o"),
             Pipe::Ground(false) => write!(f, "."),
             Pipe::Start => write!(f, "S"),
@@ -446,48 +522,227 @@ impl std::fmt::Display for Pipe {
     }
 }
 
-impl Coord {
-    fn left(&self) -> Option<Coord> {
-        if self.x == 0 {
-            None
-        } else {
-            Some(Coord {
-                x: self.x - 1,
-                y: self.y,
-            })
-        }
+#[cfg(test)]
+mod tests {
+    use super::{Coord, Map, MapError, Orientation, Pipe};
+
+    const SAMPLE_SQUARE: &str = "\
+.....
+.S-7.
+.|.|.
+.L-J.
+.....
+";
+    const SAMPLE_SQUARE_JUNK: &str = "\
+-L|F7
+7S-7|
+L|7||
+-L-J|
+L|-JF
+";
+    const SAMPLE_COMPLEX: &str = "\
+..F7.
+.FJ|.
+SJ.L7
+|F--J
+LJ...
+";
+    const SAMPLE_COMPLEX_JUNK: &str = "\
+7-F7-
+.FJ|7
+SJLL7
+|F--J
+LJ.LJ
+";
+    const SAMPLE_ENCLOSED: &str = "\
+...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........
+";
+    const SAMPLE_SQUEEZED: &str = "\
+..........
+.S------7.
+.|F----7|.
+.||....||.
+.||....||.
+.|L-7F-J|.
+.|..||..|.
+.L--JL--J.
+..........
+";
+    const SAMPLE_LARGER: &str = "\
+.F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...
+";
+    const SAMPLE_LARGER_JUNK: &str = "\
+FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L
+";
+
+    crate::sample_tests!(super::Day10 {
+        part1_square: part1(SAMPLE_SQUARE) == 4,
+        part1_square_junk: part1(SAMPLE_SQUARE_JUNK) == 4,
+        part1_complex: part1(SAMPLE_COMPLEX) == 8,
+        part1_complex_junk: part1(SAMPLE_COMPLEX_JUNK) == 8,
+        part2_square: part2(SAMPLE_SQUARE) == 1,
+        part2_square_junk: part2(SAMPLE_SQUARE_JUNK) == 1,
+        part2_complex: part2(SAMPLE_COMPLEX) == 1,
+        part2_complex_junk: part2(SAMPLE_COMPLEX_JUNK) == 1,
+        part2_enclosed: part2(SAMPLE_ENCLOSED) == 4,
+        part2_squeezed: part2(SAMPLE_SQUEEZED) == 4,
+        part2_larger: part2(SAMPLE_LARGER) == 8,
+        part2_larger_junk: part2(SAMPLE_LARGER_JUNK) == 10,
+    });
+
+    #[test]
+    fn trace_loop() {
+        let map: Map = SAMPLE_COMPLEX_JUNK.parse().unwrap();
+        let pipe_loop = map.trace_loop().unwrap();
+        assert_eq!(pipe_loop.len(), 16);
+        assert_eq!(pipe_loop.start(), Coord { x: 0, y: 2 });
+        assert_eq!(pipe_loop.start_pipe(), Pipe::SouthToEast(true));
+        assert_eq!(
+            pipe_loop.tiles()[1..4],
+            [Coord::new(1, 2), Coord::new(1, 1), Coord::new(2, 1)]
+        );
+        assert_eq!(pipe_loop.farthest(), (Coord { x: 4, y: 2 }, 8));
+        // Part 1 needs none of the flooding.
+        assert!(pipe_loop.enclosed.get().is_none());
+        assert_eq!(pipe_loop.enclosed(), [Coord { x: 2, y: 2 }]);
+        assert_eq!(pipe_loop.orientation(), Orientation::Clockwise);
     }
 
-    fn right(&self, map: &Map) -> Option<Coord> {
-        if self.x == map.map[0].len() - 1 {
-            None
-        } else {
-            Some(Coord {
-                x: self.x + 1,
-                y: self.y,
-            })
-        }
+    #[test]
+    fn enclosed_tiles_and_orientation() {
+        let map: Map = SAMPLE_ENCLOSED.parse().unwrap();
+        let pipe_loop = map.trace_loop().unwrap();
+        let enclosed = [(2, 6), (3, 6), (7, 6), (8, 6)].map(|(x, y)| Coord { x, y });
+        assert_eq!(pipe_loop.enclosed(), enclosed);
+        assert_eq!(pipe_loop.start_pipe(), Pipe::SouthToEast(true));
+        // The walk starts going right from the top left corner.
+        assert_eq!(pipe_loop.orientation(), Orientation::Clockwise);
+
+        // Going up along the right side first.
+        let map: Map = "F7\nLS".parse().unwrap();
+        let pipe_loop = map.trace_loop().unwrap();
+        assert_eq!(pipe_loop.start_pipe(), Pipe::NorthToWest(true));
+        assert_eq!(pipe_loop.orientation(), Orientation::CounterClockwise);
+        assert!(pipe_loop.enclosed().is_empty());
     }
 
-    fn up(&self) -> Option<Coord> {
-        if self.y == 0 {
-            None
-        } else {
-            Some(Coord {
-                x: self.x,
-                y: self.y - 1,
-            })
-        }
+    #[test]
+    fn malformed_maps() {
+        let error = |map: &str| {
+            map.parse::<Map>()
+                .and_then(|map| map.trace_loop().map(drop))
+                .unwrap_err()
+        };
+        assert_eq!(error("F7\nLJ"), MapError::MissingStart);
+        assert_eq!(
+            error("S7\nLS"),
+            MapError::MultipleStarts {
+                first: Coord { x: 0, y: 0 },
+                second: Coord { x: 1, y: 1 }
+            }
+        );
+        assert_eq!(
+            error("S7\nLJ."),
+            MapError::RaggedRow {
+                coord: Coord { x: 3, y: 1 },
+                expected: 2
+            }
+        );
+        assert_eq!(
+            error("S7\nLX"),
+            MapError::InvalidTile {
+                coord: Coord { x: 1, y: 1 },
+                c: 'X'
+            }
+        );
+        assert_eq!(
+            error("S-7\nL-."),
+            MapError::DeadEnd {
+                coord: Coord { x: 2, y: 0 }
+            }
+        );
+        assert_eq!(
+            error("S-\n.."),
+            MapError::DeadEnd {
+                coord: Coord { x: 0, y: 0 }
+            }
+        );
+        assert_eq!(
+            error(".|.\n-S-\n.|."),
+            MapError::BranchingLoop {
+                coord: Coord { x: 1, y: 1 },
+                connections: 4
+            }
+        );
+        assert_eq!(error("S\n").to_string(), "The loop ends at (0, 0)");
     }
 
-    fn down(&self, map: &Map) -> Option<Coord> {
-        if self.y == map.map.len() - 1 {
-            None
-        } else {
-            Some(Coord {
-                x: self.x,
-                y: self.y + 1,
-            })
-        }
+    #[test]
+    fn render() {
+        let map: Map = SAMPLE_SQUARE_JUNK.parse().unwrap();
+        let expected = "\
+─└│┌┐
+┐S─┐│
+└│┐││
+─└─┘│
+└│─┘┌";
+        let pipe_loop = map.trace_loop().unwrap();
+        assert_eq!(map.render(&pipe_loop, Some(pipe_loop.enclosed()), false), expected);
+
+        let map: Map = SAMPLE_SQUARE.parse().unwrap();
+        let pipe_loop = map.trace_loop().unwrap();
+        let expected = "\
+.....
+.S─┐.
+.│.│.
+.└─┘.
+.....";
+        assert_eq!(map.render(&pipe_loop, None, false), expected);
+        let expected = "\
+OOOOO
+OS─┐O
+O│I│O
+O└─┘O
+OOOOO";
+        assert_eq!(map.render(&pipe_loop, Some(pipe_loop.enclosed()), false), expected);
+    }
+
+    #[test]
+    fn render_expanded() {
+        let map: Map = "FS\nLJ".parse().unwrap();
+        let expected = "\
+OOOOOO
+O┌──┐O
+O│II│O
+O│II│O
+O└──┘O
+OOOOOO";
+        assert_eq!(map.render_expanded(&map.trace_loop().unwrap(), false), expected);
     }
 }
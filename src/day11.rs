@@ -1,123 +1,186 @@
 use std::str::FromStr;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use itertools::Itertools;
 
-pub fn part1(input: &str) -> Result<i64> {
-    let mut space = Space::from_str(input)?;
-    let expansion = 2;
-    space.calc_expansion_factors();
+use crate::{
+    answer::Answer,
+    grid::{Coord, Grid},
+    solution::{Metadata, Solution},
+};
 
-    Ok(space.galaxies.iter().combinations(2).fold(0, |acc, val| {
-        acc + val[0]
-            .apply_multiplier(expansion)
-            .dist(&val[1].apply_multiplier(expansion))
-    }))
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Space;
+
+    const METADATA: Metadata = Metadata {
+        day: 11,
+        title: "Cosmic Expansion",
+    };
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
 }
 
-pub fn part2(input: &str) -> Result<i64> {
-    let mut space = Space::from_str(input)?;
-    let expansion = 1_000_000;
-    space.calc_expansion_factors();
+pub fn part1(space: &Space) -> Result<usize> {
+    Ok(space.distance_sum(Expansion::uniform(2)))
+}
 
-    Ok(space.galaxies.iter().combinations(2).fold(0, |acc, val| {
-        acc + val[0]
-            .apply_multiplier(expansion)
-            .dist(&val[1].apply_multiplier(expansion))
-    }))
+pub fn part2(space: &Space) -> Result<usize> {
+    Ok(space.distance_sum(Expansion::uniform(1_000_000)))
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-struct Coord {
-    x: usize,
-    y: usize,
-    x_multipier: usize,
-    y_multipier: usize,
+/// Number of rows and columns every empty row and column grows into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Expansion {
+    pub rows: usize,
+    pub columns: usize,
 }
 
-impl Coord {
-    fn dist(&self, other: &Self) -> i64 {
-        (self.x as i64 - other.x as i64).abs() + (self.y as i64 - other.y as i64).abs()
-    }
-    fn new(x: usize, y: usize) -> Self {
+impl Expansion {
+    pub fn uniform(factor: usize) -> Self {
         Self {
-            x,
-            y,
-            x_multipier: 0,
-            y_multipier: 0,
+            rows: factor,
+            columns: factor,
         }
     }
-    fn apply_multiplier(&self, expansion_factor: usize) -> Self {
-        let mut x = self.x;
-        let mut y = self.y;
-        if self.x_multipier > 0 {
-            x += (expansion_factor * self.x_multipier) - self.x_multipier;
-        }
-        if self.y_multipier > 0 {
-            y += (expansion_factor * self.y_multipier) - self.y_multipier;
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+struct Galaxy {
+    position: Coord,
+    empty_columns_before: usize,
+    empty_rows_before: usize,
+}
+
+impl Galaxy {
+    fn new(position: Coord) -> Self {
+        Self {
+            position,
+            empty_columns_before: 0,
+            empty_rows_before: 0,
         }
+    }
 
-        Self::new(x, y)
+    fn expanded(&self, expansion: Expansion) -> Coord {
+        let Coord { x, y } = self.position;
+        Coord {
+            x: x - self.empty_columns_before + self.empty_columns_before * expansion.columns,
+            y: y - self.empty_rows_before + self.empty_rows_before * expansion.rows,
+        }
     }
 }
 
 #[derive(Debug)]
-struct Space {
-    galaxies: Vec<Coord>,
+pub struct Space {
+    /// `true` where the image shows a galaxy.
+    image: Grid<bool>,
+    galaxies: Vec<Galaxy>,
 }
 
 impl Space {
-    fn size(&self) -> (usize, usize) {
-        let mut max_x = 0;
-        let mut max_y = 0;
-        for galaxy in &self.galaxies {
-            if galaxy.x > max_x {
-                max_x = galaxy.x;
-            }
-            if galaxy.y > max_y {
-                max_y = galaxy.y;
-            }
-        }
-        (max_y + 1, max_x + 1)
+    /// Positions of the galaxies in the image row by row, galaxies are referred to by their index in here.
+    pub fn galaxies(&self) -> impl Iterator<Item = Coord> + '_ {
+        self.galaxies.iter().map(|galaxy| galaxy.position)
+    }
+
+    /// Positions of the galaxies after the space expanded.
+    pub fn expanded(&self, expansion: Expansion) -> Vec<Coord> {
+        self.galaxies.iter().map(|galaxy| galaxy.expanded(expansion)).collect()
+    }
+
+    /// Length of the shortest path between two galaxies, `None` when there is no galaxy at one of the indices.
+    pub fn distance(&self, a: usize, b: usize, expansion: Expansion) -> Option<usize> {
+        let a = self.galaxies.get(a)?.expanded(expansion);
+        let b = self.galaxies.get(b)?.expanded(expansion);
+        Some(a.manhattan_distance(b))
     }
 
-    fn is_row_empty(&self, row: usize) -> bool {
-        !self.galaxies.iter().any(|g| g.y == row.try_into().unwrap())
+    /// Sum of the distances between every pair of galaxies.
+    pub fn distance_sum(&self, expansion: Expansion) -> usize {
+        let expanded = self.expanded(expansion);
+        // The axes add up separately, in sorted order every value is at least as large as the ones before it.
+        let axis_sum = |mut values: Vec<usize>| {
+            values.sort_unstable();
+            values
+                .iter()
+                .enumerate()
+                .scan(0, |before, (idx, value)| {
+                    let sum = value * idx - *before;
+                    *before += value;
+                    Some(sum)
+                })
+                .sum::<usize>()
+        };
+        axis_sum(expanded.iter().map(|c| c.x).collect()) + axis_sum(expanded.iter().map(|c| c.y).collect())
     }
 
-    fn is_col_empty(&self, col: usize) -> bool {
-        !self.galaxies.iter().any(|g| g.x == col.try_into().unwrap())
+    /// Index of and distance to the closest other galaxy of every galaxy, ties go to the lowest index.
+    ///
+    /// `None` for a lone galaxy.
+    pub fn nearest_neighbours(&self, expansion: Expansion) -> Vec<Option<(usize, usize)>> {
+        let expanded = self.expanded(expansion);
+        let by_x = (0..expanded.len()).sorted_by_key(|idx| expanded[*idx].x).collect_vec();
+
+        let mut res = vec![None; expanded.len()];
+        for (rank, &idx) in by_x.iter().enumerate() {
+            let position = expanded[idx];
+            let mut best = None;
+            closest_on_side(position, by_x[..rank].iter().rev(), &expanded, &mut best);
+            closest_on_side(position, by_x[rank + 1..].iter(), &expanded, &mut best);
+            res[idx] = best;
+        }
+        res
     }
 
     fn calc_expansion_factors(&mut self) {
-        let start = std::time::Instant::now();
-        let (rows, cols) = self.size();
-        let mut empty_rows_reached = 0;
-        let empty_cols = (0..cols).map(|x| self.is_col_empty(x)).collect_vec();
-        for y in 0..rows {
-            if self.is_row_empty(y) {
-                empty_rows_reached += 1;
-                continue;
-            }
-
-            let mut empty_cols_reached = 0;
-            for x in 0..cols {
-                if empty_cols[x] {
-                    empty_cols_reached += 1;
-                    continue;
-                }
-
-                if let Some(coord) = self.galaxies.iter_mut().find(|g| g.x == x && g.y == y) {
-                    if empty_cols_reached > 0 {
-                        coord.x_multipier = empty_cols_reached;
-                    }
-                    if empty_rows_reached > 0 {
-                        coord.y_multipier = empty_rows_reached;
-                    }
-                }
-            }
+        let _span = tracing::debug_span!("calc_expansion_factors", galaxies = self.galaxies.len()).entered();
+        let count_empty_before = |empty: Vec<bool>| {
+            empty
+                .iter()
+                .scan(0, |count, is_empty| {
+                    let before = *count;
+                    *count += usize::from(*is_empty);
+                    Some(before)
+                })
+                .collect_vec()
+        };
+        let empty_rows_before = count_empty_before(self.image.rows().map(|row| !row.contains(&true)).collect());
+        let empty_cols_before = count_empty_before(self.image.columns().map(|mut col| !col.any(|g| *g)).collect());
+
+        for galaxy in self.galaxies.iter_mut() {
+            galaxy.empty_columns_before = empty_cols_before[galaxy.position.x];
+            galaxy.empty_rows_before = empty_rows_before[galaxy.position.y];
+        }
+    }
+}
+
+/// Keeps the closest galaxy in `best`, walking `others` away from `position` in column order.
+fn closest_on_side<'a>(
+    position: Coord,
+    others: impl Iterator<Item = &'a usize>,
+    expanded: &[Coord],
+    best: &mut Option<(usize, usize)>,
+) {
+    for &other in others {
+        // Galaxies further along this side cannot be closer once the columns alone are too far apart.
+        if best.is_some_and(|(_, distance)| expanded[other].x.abs_diff(position.x) > distance) {
+            break;
+        }
+        let distance = position.manhattan_distance(expanded[other]);
+        if best.is_none_or(|(best, best_distance)| (distance, other) < (best_distance, best)) {
+            *best = Some((other, distance));
         }
-        println!("Expand took {:?}", start.elapsed());
     }
 }
 
@@ -125,17 +188,87 @@ impl FromStr for Space {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let galaxies = s
-            .lines()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.chars().enumerate().filter_map(move |(x, c)| match c {
-                    '#' => Some(Coord::new(x, y)),
-                    _ => None,
-                })
-            })
+        let image = Grid::parse_with(s, |coord, c| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            c => bail!("Invalid character '{}' at {}", c, coord),
+        })?;
+        let galaxies = image
+            .iter()
+            .filter(|(_, is_galaxy)| **is_galaxy)
+            .map(|(position, _)| Galaxy::new(position))
             .collect_vec();
 
-        Ok(Space { galaxies })
+        let mut space = Space { image, galaxies };
+        space.calc_expansion_factors();
+        Ok(space)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::{Expansion, Space};
+
+    const SAMPLE: &str = "\
+...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....
+";
+
+    crate::sample_tests!(super::Day11 {
+        part1_sample: part1(SAMPLE) == 374,
+        part2_sample: part2(SAMPLE) == 82000210,
+    });
+
+    #[test]
+    fn distances_between_galaxies() {
+        let space: Space = SAMPLE.parse().unwrap();
+        let expansion = Expansion::uniform(2);
+        assert_eq!(space.distance(4, 8, expansion), Some(9));
+        assert_eq!(space.distance(0, 6, expansion), Some(15));
+        assert_eq!(space.distance(2, 5, expansion), Some(17));
+        assert_eq!(space.distance(7, 8, expansion), Some(5));
+        assert_eq!(space.distance(7, 9, expansion), None);
+        assert_eq!(space.distance_sum(Expansion::uniform(10)), 1030);
+        assert_eq!(space.distance_sum(Expansion::uniform(100)), 8410);
+    }
+
+    #[test]
+    fn separate_axes() {
+        let space: Space = SAMPLE.parse().unwrap();
+        let expansion = Expansion { rows: 3, columns: 7 };
+        let brute_force: usize = (0..space.galaxies().count())
+            .tuple_combinations()
+            .map(|(a, b)| space.distance(a, b, expansion).unwrap())
+            .sum();
+        assert_eq!(space.distance_sum(expansion), brute_force);
+        // Galaxies 1 and 2 are 4 columns apart including an empty one growing by 6, and a row apart.
+        assert_eq!(space.distance(0, 1, expansion), Some(4 + 6 + 1));
+    }
+
+    #[test]
+    fn nearest_neighbours() {
+        let space: Space = SAMPLE.parse().unwrap();
+        let expansion = Expansion { rows: 1, columns: 5 };
+        let count = space.galaxies().count();
+        let brute_force = (0..count)
+            .map(|a| {
+                (0..count)
+                    .filter(|b| *b != a)
+                    .map(|b| (b, space.distance(a, b, expansion).unwrap()))
+                    .min_by_key(|(b, distance)| (*distance, *b))
+            })
+            .collect_vec();
+        assert_eq!(space.nearest_neighbours(expansion), brute_force);
+        assert_eq!(space.nearest_neighbours(Expansion::uniform(2))[7], Some((8, 5)));
     }
 }
@@ -3,8 +3,36 @@ use std::{collections::HashMap, str::FromStr};
 use anyhow::{bail, Context, Result};
 use itertools::Itertools;
 
-pub fn part1(input: &str) -> Result<usize> {
-    let mut binding = input.parse::<Springs>()?;
+use crate::{
+    answer::Answer,
+    solution::{Metadata, Solution},
+};
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Springs;
+
+    const METADATA: Metadata = Metadata {
+        day: 12,
+        title: "Hot Springs",
+    };
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+}
+
+pub fn part1(input: &Springs) -> Result<usize> {
+    let mut binding = input.clone();
     let results = binding
         .0
         .iter_mut()
@@ -12,8 +40,8 @@ pub fn part1(input: &str) -> Result<usize> {
 
     Ok(results)
 }
-pub fn part2(input: &str) -> Result<usize> {
-    let mut binding = input.parse::<Springs>()?;
+pub fn part2(input: &Springs) -> Result<usize> {
+    let mut binding = input.clone();
 
     // expand the input
     for row in binding.0.iter_mut() {
@@ -41,6 +69,7 @@ enum Spring {
     Working,
 }
 
+#[derive(Clone)]
 struct SpringsRow {
     springs: Vec<Spring>,
     lengths: Vec<usize>,
@@ -69,12 +98,12 @@ impl SpringsRow {
             }
 
             // Working spring in the range we are checking, skip
-            if self.springs[i..i + length].iter().any(|v| *v == Spring::Working) {
+            if self.springs[i..i + length].contains(&Spring::Working) {
                 continue;
             }
 
             // Damaged spring was skipped, no other solution from this attempt is valid
-            if self.springs[start_idx..i].iter().any(|v| *v == Spring::Damaged) {
+            if self.springs[start_idx..i].contains(&Spring::Damaged) {
                 break;
             }
 
@@ -102,7 +131,8 @@ impl SpringsRow {
     }
 }
 
-struct Springs(Vec<SpringsRow>);
+#[derive(Clone)]
+pub struct Springs(Vec<SpringsRow>);
 
 impl FromStr for Springs {
     type Err = anyhow::Error;
@@ -133,3 +163,20 @@ impl FromStr for Springs {
             .map(Springs)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE: &str = "\
+???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1
+";
+
+    crate::sample_tests!(super::Day12 {
+        part1_sample: part1(SAMPLE) == 21,
+        part2_sample: part2(SAMPLE) == 525152,
+    });
+}
@@ -3,18 +3,45 @@ use std::str::FromStr;
 use anyhow::{Context, Result};
 use itertools::Itertools;
 
-pub fn part1(input: &str) -> Result<usize> {
-    let grids: Grids = input.parse()?;
+use crate::{
+    answer::Answer,
+    grid::Grid,
+    solution::{Metadata, Solution},
+};
 
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Grids;
+
+    const METADATA: Metadata = Metadata {
+        day: 13,
+        title: "Point of Incidence",
+    };
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+}
+
+pub fn part1(grids: &Grids) -> Result<usize> {
     let mut result = 0;
-    for mut grid in grids.0 {
-        for row_idx in 0..grid.data.len() - 1 {
+    for grid in grids.0.iter() {
+        for row_idx in 0..grid.data.height() - 1 {
             if grid.mirrored_at_rows(row_idx, row_idx + 1) == 0 {
                 result += 100 * (row_idx + 1);
             }
         }
 
-        for col_idx in 0..grid.data[0].len() - 1 {
+        for col_idx in 0..grid.data.width() - 1 {
             if grid.mirrored_at_cols(col_idx, col_idx + 1) == 0 {
                 result += col_idx + 1;
             }
@@ -24,18 +51,16 @@ pub fn part1(input: &str) -> Result<usize> {
     Ok(result)
 }
 
-pub fn part2(input: &str) -> Result<usize> {
-    let grids: Grids = input.parse()?;
-
+pub fn part2(grids: &Grids) -> Result<usize> {
     let mut result = 0;
-    for mut grid in grids.0 {
-        for row_idx in 0..grid.data.len() - 1 {
+    for grid in grids.0.iter() {
+        for row_idx in 0..grid.data.height() - 1 {
             if grid.mirrored_at_rows(row_idx, row_idx + 1) == 1 {
                 result += 100 * (row_idx + 1);
             }
         }
 
-        for col_idx in 0..grid.data[0].len() - 1 {
+        for col_idx in 0..grid.data.width() - 1 {
             if grid.mirrored_at_cols(col_idx, col_idx + 1) == 1 {
                 result += col_idx + 1;
             }
@@ -45,12 +70,12 @@ pub fn part2(input: &str) -> Result<usize> {
     Ok(result)
 }
 
-struct Grid {
-    data: Vec<Vec<char>>,
+struct Pattern {
+    data: Grid<char>,
 }
 
-impl Grid {
-    fn mirrored_at_rows(&mut self, top: usize, bottom: usize) -> i32 {
+impl Pattern {
+    fn mirrored_at_rows(&self, top: usize, bottom: usize) -> i32 {
         let mut top = top;
         let mut bottom = bottom;
 
@@ -60,7 +85,7 @@ impl Grid {
             if result != 0 {
                 differences += result;
             }
-            if top == 0 || bottom == self.data.len() - 1 {
+            if top == 0 || bottom == self.data.height() - 1 {
                 break;
             }
 
@@ -71,7 +96,7 @@ impl Grid {
         differences
     }
 
-    fn mirrored_at_cols(&mut self, left: usize, right: usize) -> i32 {
+    fn mirrored_at_cols(&self, left: usize, right: usize) -> i32 {
         let mut left = left;
         let mut right = right;
 
@@ -82,7 +107,7 @@ impl Grid {
                 differences += result;
             }
 
-            if left == 0 || right == self.data[0].len() - 1 {
+            if left == 0 || right == self.data.width() - 1 {
                 break;
             }
             left -= 1;
@@ -92,23 +117,25 @@ impl Grid {
         differences
     }
 
-    fn row_eq(&mut self, row_idx: usize, other_row_idx: usize) -> i32 {
-        self.data[row_idx]
+    fn row_eq(&self, row_idx: usize, other_row_idx: usize) -> i32 {
+        self.data
+            .row(row_idx)
             .iter()
-            .zip(self.data[other_row_idx].iter())
+            .zip(self.data.row(other_row_idx))
             .filter(|(a, b)| a != b)
             .count() as i32
     }
 
-    fn col_eq(&mut self, col_idx: usize, other_col_idx: usize) -> i32 {
+    fn col_eq(&self, col_idx: usize, other_col_idx: usize) -> i32 {
         self.data
-            .iter()
-            .filter(|row| row[col_idx] != row[other_col_idx])
+            .column(col_idx)
+            .zip(self.data.column(other_col_idx))
+            .filter(|(a, b)| a != b)
             .count() as i32
     }
 }
 
-struct Grids(Vec<Grid>);
+pub struct Grids(Vec<Pattern>);
 
 impl FromStr for Grids {
     type Err = anyhow::Error;
@@ -123,12 +150,36 @@ impl FromStr for Grids {
     }
 }
 
-impl FromStr for Grid {
+impl FromStr for Pattern {
     type Err = anyhow::Error;
 
     fn from_str(input: &str) -> Result<Self> {
-        Ok(Grid {
-            data: input.lines().map(|l| l.chars().collect_vec()).collect_vec(),
-        })
+        Ok(Pattern { data: input.parse()? })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE: &str = "\
+#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#
+";
+
+    crate::sample_tests!(super::Day13 {
+        part1_sample: part1(SAMPLE) == 405,
+        part2_sample: part2(SAMPLE) == 400,
+    });
+}
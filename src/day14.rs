@@ -1,185 +1,412 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{fmt::Display, str::FromStr};
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use itertools::Itertools;
 
-pub fn part1(input: &str) -> Result<usize> {
-    let mut grid = input.parse::<Grid>()?;
-
-    grid.roll_north();
-
-    Ok(grid
-        .grid
-        .iter()
-        .filter(|(_, r)| r == &&Rock::Round)
-        .map(|((y, _), _)| grid.height - y)
-        .sorted()
-        .sum())
-}
-pub fn part2(input: &str) -> Result<usize> {
-    let mut grid = input.parse::<Grid>()?;
-    let cycles = 1_000_000_000;
-    let mut history = HashMap::new();
-
-    let mut cycle_start = 0;
-    let mut cycle_len = 0;
-    for i in 0..cycles {
-        grid.roll_north();
-        grid.roll_west();
-        grid.roll_south();
-        grid.roll_east();
-        if let Some(start) = history.insert(grid.to_key(), i + 1) {
-            cycle_start = start;
-            cycle_len = i - cycle_start + 1;
-            break;
-        }
+use crate::{
+    answer::Answer,
+    cycle,
+    grid::{Coord, Direction, Grid},
+    solution::{Metadata, Solution},
+};
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Platform;
+
+    const METADATA: Metadata = Metadata {
+        day: 14,
+        title: "Parabolic Reflector Dish",
+    };
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
     }
 
-    let left_to_process = (cycles - cycle_start) % cycle_len;
-    for _ in 0..left_to_process {
-        grid.roll_north();
-        grid.roll_west();
-        grid.roll_south();
-        grid.roll_east();
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
     }
+}
+
+pub fn part1(platform: &Platform) -> Result<usize> {
+    let north: TiltSequence = "N".parse()?;
+    Ok(platform.tilted(&north, 1).load(Direction::Up))
+}
+
+/// Tilts of a single spin cycle.
+pub const SPIN_CYCLE: &str = "NWSE";
+/// Spin cycles run in part 2.
+pub const SPIN_CYCLES: usize = 1_000_000_000;
+
+pub fn part2(platform: &Platform) -> Result<usize> {
+    let spin_cycle: TiltSequence = SPIN_CYCLE.parse()?;
+    Ok(platform.tilted(&spin_cycle, SPIN_CYCLES).load(Direction::Up))
+}
+
+/// Directions to tilt a [`Platform`] in one after the other, written as compass points like `NWSE`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TiltSequence(pub Vec<Direction>);
+
+impl FromStr for TiltSequence {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        s.chars()
+            .map(|c| {
+                Ok(match c.to_ascii_uppercase() {
+                    'N' => Direction::Up,
+                    'E' => Direction::Right,
+                    'S' => Direction::Down,
+                    'W' => Direction::Left,
+                    c => bail!("Invalid tilt '{}', expected one of N, E, S or W", c),
+                })
+            })
+            .collect::<Result<_>>()
+            .map(TiltSequence)
+    }
+}
+
+impl Display for TiltSequence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for direction in &self.0 {
+            let c = match direction {
+                Direction::Up => 'N',
+                Direction::Right => 'E',
+                Direction::Down => 'S',
+                Direction::Left => 'W',
+            };
+            write!(f, "{}", c)?;
+        }
+        Ok(())
+    }
+}
 
-    Ok(grid
-        .grid
-        .iter()
-        .filter(|(_, r)| r == &&Rock::Round)
-        .map(|((y, _), _)| grid.height - y)
-        .sorted()
-        .sum())
+/// Load of the round rocks on each side of a [`Platform`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Loads {
+    pub north: usize,
+    pub east: usize,
+    pub south: usize,
+    pub west: usize,
 }
 
-#[derive(PartialEq, Eq, Clone)]
-enum Rock {
+impl Display for Loads {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "north {}, east {}, south {}, west {}",
+            self.north, self.east, self.south, self.west
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rock {
     Round,
     Cube,
+    Empty,
 }
-#[derive(Clone, PartialEq, Eq)]
-struct Grid {
-    grid: HashMap<(usize, usize), Rock>,
+
+impl TryFrom<char> for Rock {
+    type Error = anyhow::Error;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        Ok(match c {
+            'O' => Rock::Round,
+            '#' => Rock::Cube,
+            '.' => Rock::Empty,
+            c => bail!("Invalid rock '{}'", c),
+        })
+    }
+}
+
+impl Display for Rock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let c = match self {
+            Rock::Round => 'O',
+            Rock::Cube => '#',
+            Rock::Empty => '.',
+        };
+        write!(f, "{}", c)
+    }
+}
+
+/// Widest platform a row bitset can hold.
+const MAX_WIDTH: usize = u128::BITS as usize;
+
+/// Rocks packed into one bitset per row, bit `x` of a row is the rock in column `x`.
+///
+/// Tilting north or south moves whole rows at once and tilting east or west shifts bits within each row, so there
+/// are no column bitsets. A row is a single `u128`, parsing fails for platforms wider than 128 columns.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Platform {
     width: usize,
-    height: usize,
+    round: Vec<u128>,
+    cube: Vec<u128>,
 }
 
-impl Grid {
-    fn to_key(&self) -> Vec<(usize, usize)> {
-        self.grid.keys().copied().collect_vec()
+impl Platform {
+    pub fn width(&self) -> usize {
+        self.width
     }
 
-    fn roll_north(&mut self) {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let key = match self.grid.get(&(y, x)) {
-                    Some(val) if val == &Rock::Cube => continue,
-                    None => continue,
-                    _ => (y, x),
-                };
-                let mut y = key.0;
-
-                loop {
-                    if y == 0 {
-                        break;
-                    }
-                    if self.grid.contains_key(&(y - 1, key.1)) {
-                        break;
-                    }
-                    y -= 1;
-                }
-                self.grid.remove(&key);
-                self.grid.insert((y, key.1), Rock::Round);
-            }
+    pub fn height(&self) -> usize {
+        self.round.len()
+    }
+
+    pub fn get(&self, coord: Coord) -> Option<Rock> {
+        if coord.x >= self.width || coord.y >= self.height() {
+            return None;
         }
+        let bit = 1 << coord.x;
+        Some(if self.round[coord.y] & bit != 0 {
+            Rock::Round
+        } else if self.cube[coord.y] & bit != 0 {
+            Rock::Cube
+        } else {
+            Rock::Empty
+        })
     }
 
-    fn roll_south(&mut self) {
-        let keys = self.grid.keys().copied().sorted_by(|a, b| b.0.cmp(&a.0)).collect_vec();
-        for key in keys {
-            if self.grid.get(&key).is_some_and(|r| r == &Rock::Cube) {
-                continue;
-            }
-            let mut y = key.0;
+    pub fn grid(&self) -> Grid<Rock> {
+        let mut grid = Grid::new(self.width, self.height(), Rock::Empty);
+        for coord in grid.coords().collect_vec() {
+            grid[coord] = self.get(coord).expect("The grid has the size of the platform");
+        }
+        grid
+    }
 
-            loop {
-                if y == self.height - 1 {
-                    break;
-                }
-                if self.grid.contains_key(&(y + 1, key.1)) {
-                    break;
-                }
-                y += 1;
-            }
-            self.grid.remove(&key);
-            self.grid.insert((y, key.1), Rock::Round);
+    /// Every round rock adds its distance to the edge opposite of `side`, counting its own row or column.
+    pub fn load(&self, side: Direction) -> usize {
+        let height = self.height();
+        self.round
+            .iter()
+            .enumerate()
+            .map(|(y, row)| match side {
+                Direction::Up => (height - y) * row.count_ones() as usize,
+                Direction::Down => (y + 1) * row.count_ones() as usize,
+                Direction::Left => columns(*row).map(|x| self.width - x).sum(),
+                Direction::Right => columns(*row).map(|x| x + 1).sum(),
+            })
+            .sum()
+    }
+
+    pub fn loads(&self) -> Loads {
+        Loads {
+            north: self.load(Direction::Up),
+            east: self.load(Direction::Right),
+            south: self.load(Direction::Down),
+            west: self.load(Direction::Left),
         }
     }
 
-    fn roll_west(&mut self) {
-        let keys = self.grid.keys().copied().sorted_by(|a, b| a.1.cmp(&b.1)).collect_vec();
-        for key in keys {
-            if self.grid.get(&key).is_some_and(|r| r == &Rock::Cube) {
-                continue;
-            }
-            let mut x = key.1;
+    /// 128-bit FNV-1a hash of the round rocks, the only rocks that move.
+    pub fn fingerprint(&self) -> u128 {
+        const OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+        const PRIME: u128 = 0x0000000001000000000000000000013b;
+        self.round
+            .iter()
+            .fold(OFFSET_BASIS, |hash, row| (hash ^ row).wrapping_mul(PRIME))
+    }
 
-            loop {
-                if x == 0 {
-                    break;
-                }
-                if self.grid.contains_key(&(key.0, x - 1)) {
-                    break;
-                }
-                x -= 1;
+    /// The platform after running `sequence` `repeats` times, skipping ahead once the platform repeats itself.
+    pub fn tilted(&self, sequence: &TiltSequence, repeats: usize) -> Platform {
+        let step = |platform: &Platform| {
+            let mut platform = platform.clone();
+            platform.tilt_all(sequence);
+            platform
+        };
+        cycle::hashed_state_at(self.clone(), step, Platform::fingerprint, repeats)
+    }
+
+    pub fn tilt_all(&mut self, sequence: &TiltSequence) {
+        for direction in &sequence.0 {
+            self.tilt(*direction);
+        }
+    }
+
+    /// Rolls every round rock as far as it goes in `direction`.
+    ///
+    /// Every pass moves all rocks with free space next to them by one step at once, until none can move.
+    pub fn tilt(&mut self, direction: Direction) {
+        match direction {
+            Direction::Up => self.tilt_vertically(|y| y.checked_sub(1)),
+            Direction::Down => {
+                let height = self.height();
+                self.tilt_vertically(|y| Some(y + 1).filter(|y| *y < height))
             }
-            self.grid.remove(&key);
-            self.grid.insert((key.0, x), Rock::Round);
+            Direction::Left => self.tilt_horizontally(|free| free << 1, |moving| moving >> 1),
+            Direction::Right => self.tilt_horizontally(|free| free >> 1, |moving| moving << 1),
         }
     }
 
-    fn roll_east(&mut self) {
-        let keys = self.grid.keys().copied().sorted_by(|a, b| b.1.cmp(&a.1)).collect_vec();
-        for key in keys {
-            if self.grid.get(&key).is_some_and(|r| r == &Rock::Cube) {
-                continue;
+    /// Moves rocks from each row to the row `towards` returns, it returns `None` at the edge.
+    fn tilt_vertically(&mut self, towards: impl Fn(usize) -> Option<usize>) {
+        let mut moved = true;
+        while moved {
+            moved = false;
+            for y in 0..self.height() {
+                let Some(next) = towards(y) else {
+                    continue;
+                };
+                let moving = self.round[y] & !(self.round[next] | self.cube[next]);
+                if moving != 0 {
+                    self.round[y] ^= moving;
+                    self.round[next] |= moving;
+                    moved = true;
+                }
             }
-            let mut x = key.1;
+        }
+    }
 
+    /// Moves rocks within each row, `to_rocks` lines free cells up with the rocks next to them and `step` moves rocks
+    /// onto the free cells.
+    fn tilt_horizontally(&mut self, to_rocks: impl Fn(u128) -> u128, step: impl Fn(u128) -> u128) {
+        // Shifting by all 128 bits would overflow, an empty row has no inside.
+        let inside = u128::MAX.checked_shr((MAX_WIDTH - self.width) as u32).unwrap_or(0);
+        for (round, cube) in self.round.iter_mut().zip(&self.cube) {
             loop {
-                if x == self.width - 1 {
-                    break;
-                }
-                if self.grid.contains_key(&(key.0, x + 1)) {
+                let free = !(*round | cube) & inside;
+                let moving = *round & to_rocks(free);
+                if moving == 0 {
                     break;
                 }
-                x += 1;
+                *round = *round ^ moving | step(moving);
             }
-            self.grid.remove(&key);
-            self.grid.insert((key.0, x), Rock::Round);
         }
     }
 }
 
-impl FromStr for Grid {
+/// Columns of the set bits of a row.
+fn columns(mut row: u128) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        (row != 0).then(|| {
+            let x = row.trailing_zeros() as usize;
+            row &= row - 1;
+            x
+        })
+    })
+}
+
+impl FromStr for Platform {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let grid: HashMap<_, _> = s
-            .lines()
-            .enumerate()
-            .flat_map(|(y, line)| {
-                line.chars().enumerate().filter_map(move |(x, c)| match c {
-                    'O' => Some(((y, x), Rock::Round)),
-                    '#' => Some(((y, x), Rock::Cube)),
-                    _ => None,
+        let grid: Grid<Rock> = s.parse()?;
+        if grid.width() > MAX_WIDTH {
+            bail!("Platforms are at most {} columns wide, got {}", MAX_WIDTH, grid.width());
+        }
+        let row_bits = |rock: Rock| {
+            grid.rows()
+                .map(|row| {
+                    row.iter()
+                        .enumerate()
+                        .filter(|(_, r)| **r == rock)
+                        .fold(0, |bits, (x, _)| bits | 1 << x)
                 })
-            })
-            .collect();
-        Ok(Grid {
-            width: grid.keys().map(|(_, x)| x).max().unwrap_or(&0) + 1,
-            height: grid.keys().map(|(y, _)| y).max().unwrap_or(&0) + 1,
-            grid,
+                .collect()
+        };
+        Ok(Platform {
+            width: grid.width(),
+            round: row_bits(Rock::Round),
+            cube: row_bits(Rock::Cube),
         })
     }
 }
+
+/// Draws the platform row by row, without a trailing newline.
+impl Display for Platform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.grid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, Loads, Platform, TiltSequence, SPIN_CYCLE};
+
+    const SAMPLE: &str = "\
+O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....
+";
+
+    crate::sample_tests!(super::Day14 {
+        part1_sample: part1(SAMPLE) == 136,
+        part2_sample: part2(SAMPLE) == 64,
+    });
+
+    #[test]
+    fn spin_cycles() {
+        let platform: Platform = SAMPLE.parse().unwrap();
+        let after_3 = "\
+.....#....
+....#...O#
+.....##...
+..O#......
+.....OOO#.
+.O#...O#.#
+....O#...O
+.......OOO
+#...O###.O
+#.OOO#...O
+";
+        let spin_cycle: TiltSequence = SPIN_CYCLE.parse().unwrap();
+        assert_eq!(platform.tilted(&spin_cycle, 3).to_string(), after_3.trim_end());
+        assert_eq!(platform.tilted(&spin_cycle, 0), platform);
+    }
+
+    #[test]
+    fn loads_on_every_side() {
+        let platform: Platform = "O.#\n..O".parse().unwrap();
+        let expected = Loads {
+            north: 2 + 1,
+            east: 1 + 3,
+            south: 1 + 2,
+            west: 3 + 1,
+        };
+        assert_eq!(platform.loads(), expected);
+
+        let tilted = platform.tilted(&"es".parse().unwrap(), 1);
+        assert_eq!(tilted.to_string(), "..#\n.OO");
+    }
+
+    #[test]
+    fn full_width_rows() {
+        let row = format!("O{}#O.", ".".repeat(124));
+        let mut platform: Platform = row.parse().unwrap();
+        platform.tilt(Direction::Right);
+        assert_eq!(platform.to_string(), format!("{}O#.O", ".".repeat(124)));
+        assert_eq!(platform.load(Direction::Right), 125 + 128);
+        assert!(format!("{}.", row).parse::<Platform>().is_err());
+    }
+
+    #[test]
+    fn empty_platform() {
+        let mut platform: Platform = "".parse().unwrap();
+        for direction in Direction::ALL {
+            platform.tilt(direction);
+        }
+        assert_eq!(platform.to_string(), "");
+        assert_eq!(platform.load(Direction::Left), 0);
+    }
+
+    #[test]
+    fn tilt_sequences() {
+        let sequence: TiltSequence = "nwSE".parse().unwrap();
+        assert_eq!(sequence.to_string(), SPIN_CYCLE);
+        assert!("NX".parse::<TiltSequence>().is_err());
+    }
+}
@@ -3,16 +3,44 @@ use std::str::FromStr;
 use anyhow::{Context, Result};
 use itertools::Itertools;
 
-pub fn part1(input: &str) -> Result<usize> {
+use crate::{
+    answer::Answer,
+    solution::{Metadata, Solution},
+};
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Vec<String>;
+
+    const METADATA: Metadata = Metadata {
+        day: 15,
+        title: "Lens Library",
+    };
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.split(',').map(|step| step.trim().to_string()).collect())
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+}
+
+pub fn part1(input: &[String]) -> Result<usize> {
     Ok(input
-        .split(',')
-        .map(|v| v.trim().chars().fold(0, |acc, val| ((acc + val as usize) * 17) % 256))
+        .iter()
+        .map(|v| v.chars().fold(0, |acc, val| ((acc + val as usize) * 17) % 256))
         .sum::<usize>())
 }
 
-pub fn part2(input: &str) -> Result<usize> {
+pub fn part2(input: &[String]) -> Result<usize> {
     Ok(input
-        .split(',')
+        .iter()
         .filter_map(|v| -> Option<Operation> { v.parse::<Operation>().ok() })
         .fold(
             (0..256).map(|_| Vec::<Lens>::new()).collect_vec(),
@@ -101,3 +129,15 @@ impl FromStr for Operation {
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE: &str = "\
+rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7
+";
+
+    crate::sample_tests!(super::Day15 {
+        part1_sample: part1(SAMPLE) == 1320,
+        part2_sample: part2(SAMPLE) == 145,
+    });
+}
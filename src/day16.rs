@@ -1,203 +1,372 @@
-use anyhow::Result;
-use itertools::Itertools;
-use std::{collections::HashMap, str::FromStr};
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+    iter, mem,
+    str::FromStr,
+    thread,
+    time::Duration,
+};
 
-pub fn part1(input: &str) -> Result<i64> {
-    Ok(input.parse::<Grid>()?.energize(Coord { x: 0, y: 0 }, Direction::Right))
+use crate::{
+    answer::Answer,
+    grid::{Coord, Direction, Grid},
+    solution::{self, Metadata, Part, Solution},
+};
+
+/// Pause between two frames of the animation.
+const FRAME_DELAY: Duration = Duration::from_millis(30);
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Contraption;
+
+    const METADATA: Metadata = Metadata {
+        day: 16,
+        title: "The Floor Will Be Lava",
+    };
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+
+    /// Animates the beams step by step on a terminal, then draws how often every tile was crossed.
+    fn visualize(input: &Self::Input, part: Part, out: &mut dyn Write) -> Result<bool> {
+        let (position, direction) = match part {
+            Part::One => (Coord { x: 0, y: 0 }, Direction::Right),
+            Part::Two => {
+                let best = input.best_entry(1).context("The contraption is empty")?;
+                (best.position, best.direction)
+            }
+        };
+        let propagation = input.propagate(position, direction);
+        let colored = colored::control::SHOULD_COLORIZE.should_colorize();
+
+        if colored {
+            let mut heat = Grid::new(input.0.width(), input.0.height(), 0);
+            for beams in &propagation.steps {
+                beams.iter().for_each(|(position, _)| heat[*position] += 1);
+                // Clear the screen and move the cursor to the top left corner.
+                write!(out, "\x1b[2J\x1b[H")?;
+                writeln!(out, "{}", input.render_beams(beams, &heat, colored))?;
+                out.flush()?;
+                thread::sleep(FRAME_DELAY);
+            }
+        }
+
+        writeln!(
+            out,
+            "Entering at {} going {:?}, {} tiles energized after {} steps",
+            position,
+            direction,
+            propagation.energized(),
+            propagation.steps.len()
+        )?;
+        writeln!(out, "{}", input.render_heatmap(&propagation.heat, colored))?;
+        Ok(true)
+    }
 }
 
-pub fn part2(input: &str) -> Result<i64> {
-    let mut grid = input.parse::<Grid>()?;
-    let grid_len = grid.0[0].len() - 1;
-
-    Ok((0..grid.0.len()).fold(0, |acc, i| {
-        [
-            (Coord { x: 0, y: i }, Direction::Right),
-            (Coord { x: grid_len, y: i }, Direction::Left),
-            (Coord { x: i, y: 0 }, Direction::Down),
-            (Coord { x: i, y: grid_len }, Direction::Up),
-        ]
-        .iter()
-        .fold(0, |acc, (coord, direction)| {
-            grid.reset();
-            grid.energize(*coord, *direction).max(acc)
-        })
-        .max(acc)
-    }))
+pub fn part1(contraption: &Contraption) -> Result<i64> {
+    Ok(contraption.energized(Coord { x: 0, y: 0 }, Direction::Right) as i64)
 }
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
-struct Coord {
-    x: usize,
-    y: usize,
+pub fn part2(contraption: &Contraption) -> Result<i64> {
+    let best = contraption
+        .best_entry(solution::threads())
+        .context("The contraption is empty")?;
+    Ok(best.energized as i64)
 }
 
-impl Coord {
-    fn move_in_direction(&self, direction: &Direction, grid: &Grid) -> Option<Self> {
-        match direction {
-            Direction::Up if self.y > 0 => Some(Self {
-                x: self.x,
-                y: self.y - 1,
-            }),
-            Direction::Down if self.y < grid.0.len() - 1 => Some(Self {
-                x: self.x,
-                y: self.y + 1,
-            }),
-            Direction::Left if self.x > 0 => Some(Self {
-                x: self.x - 1,
-                y: self.y,
-            }),
-            Direction::Right if self.x < grid.0[0].len() - 1 => Some(Self {
-                x: self.x + 1,
-                y: self.y,
-            }),
-            _ => None,
-        }
-    }
+#[derive(Debug, Clone)]
+pub struct Contraption(Grid<Tile>);
+
+/// Beam on a tile together with the direction it moves in.
+type Beam = (Coord, Direction);
+
+/// Tile on the edge of a [`Contraption`] a beam enters at and how many tiles it energizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    pub position: Coord,
+    pub direction: Direction,
+    pub energized: usize,
 }
 
+/// Path of a beam up to the splitter that splits it, shared by every beam following that path.
 #[derive(Debug, Clone)]
-struct Grid(Vec<Vec<Tile>>);
-
-impl Grid {
-    fn reset(&mut self) {
-        self.0.iter_mut().for_each(|row| {
-            row.iter_mut().for_each(|tile| match tile {
-                Tile::MirrorUp { energized: has_beam } => *has_beam = false,
-                Tile::MirrorDown { energized: has_beam } => *has_beam = false,
-                Tile::SplitterVertical { energized: has_beam } => *has_beam = false,
-                Tile::SplitterHorizontal { energized: has_beam } => *has_beam = false,
-                Tile::Empty { energized: has_beam } => *has_beam = false,
-            })
+struct Segment {
+    tiles: Vec<Coord>,
+    /// Beams leaving the splitter at the end, empty when the beam leaves the contraption or loops.
+    next: Vec<Beam>,
+}
+
+/// Beams moving through a [`Contraption`] from a single entry point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Propagation {
+    /// Tiles holding a beam after every step and the direction the beam moves in, starting with the entry point.
+    ///
+    /// A beam stops when it leaves the contraption or follows a path an earlier beam already took.
+    pub steps: Vec<Vec<(Coord, Direction)>>,
+    /// Number of directions beams crossed each tile in, 0 for tiles that are not energized.
+    pub heat: Grid<u8>,
+}
+
+impl Propagation {
+    pub fn energized(&self) -> usize {
+        self.heat.values().filter(|heat| **heat > 0).count()
+    }
+}
+
+impl Contraption {
+    /// Follows the beam entering at `position` going in `direction` one step at a time.
+    pub fn propagate(&self, position: Coord, direction: Direction) -> Propagation {
+        let mut heat = Grid::new(self.0.width(), self.0.height(), 0);
+        let mut seen = HashSet::new();
+        let mut beams = vec![(position, direction)];
+        beams.retain(|beam| self.0.contains(beam.0) && seen.insert(*beam));
+
+        let mut steps = Vec::new();
+        while !beams.is_empty() {
+            let next = beams
+                .iter()
+                .flat_map(|(position, direction)| {
+                    heat[*position] += 1;
+                    let (direction1, direction2) = self.0[*position].next(direction);
+                    iter::once(direction1)
+                        .chain(direction2)
+                        .filter_map(|direction| Some((self.0.neighbour(*position, direction)?, direction)))
+                })
+                .filter(|beam| seen.insert(*beam))
+                .collect();
+            steps.push(mem::replace(&mut beams, next));
+        }
+        Propagation { steps, heat }
+    }
+
+    /// Number of tiles energized by the beam entering at `position` going in `direction`.
+    pub fn energized(&self, position: Coord, direction: Direction) -> usize {
+        let segments = self.segments([(position, direction)]);
+        self.energized_by(&segments, (position, direction))
+    }
+
+    /// Entry point energizing the most tiles, `None` for an empty contraption.
+    ///
+    /// The entry points are split over `threads` threads, which share the segments between splitters.
+    pub fn best_entry(&self, threads: usize) -> Option<Entry> {
+        let entries = self.entries().collect::<Vec<_>>();
+        let segments = self.segments(entries.iter().copied());
+        let best_of = |entries: &[Beam]| {
+            entries
+                .iter()
+                .map(|&(position, direction)| Entry {
+                    position,
+                    direction,
+                    energized: self.energized_by(&segments, (position, direction)),
+                })
+                .max_by_key(|entry| entry.energized)
+        };
+
+        let chunk_size = entries.len().div_ceil(threads.max(1)).max(1);
+        if chunk_size >= entries.len() {
+            return best_of(&entries);
+        }
+        thread::scope(|scope| {
+            let handles = entries
+                .chunks(chunk_size)
+                .map(|chunk| scope.spawn(move || best_of(chunk)))
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .filter_map(|handle| handle.join().expect("Entry point search panicked"))
+                .max_by_key(|entry| entry.energized)
         })
     }
 
-    fn energize(&mut self, position: Coord, direction: Direction) -> i64 {
-        self.traverse(position, direction, &mut HashMap::new())
+    /// Every tile on the edge together with the direction pointing into the contraption.
+    pub fn entries(&self) -> impl Iterator<Item = (Coord, Direction)> + '_ {
+        // Without rows or columns there is no edge to enter from.
+        let (width, height) = match (self.0.width(), self.0.height()) {
+            (0, _) | (_, 0) => (0, 0),
+            size => size,
+        };
+        (0..height)
+            .flat_map(move |y| {
+                [
+                    (Coord { x: 0, y }, Direction::Right),
+                    (Coord { x: width - 1, y }, Direction::Left),
+                ]
+            })
+            .chain((0..width).flat_map(move |x| {
+                [
+                    (Coord { x, y: 0 }, Direction::Down),
+                    (Coord { x, y: height - 1 }, Direction::Up),
+                ]
+            }))
     }
 
-    fn traverse(
-        &mut self,
-        position: Coord,
-        current_source_direction: Direction,
-        cache: &mut HashMap<(Direction, Coord), ()>,
-    ) -> i64 {
-        if cache.contains_key(&(current_source_direction, position)) {
+    fn energized_by(&self, segments: &HashMap<Beam, Segment>, entry: Beam) -> usize {
+        if !self.0.contains(entry.0) {
             return 0;
         }
-        cache.insert((current_source_direction, position), ());
+        let mut energized = Grid::new(self.0.width(), self.0.height(), false);
+        let mut seen = HashSet::from([entry]);
+        let mut stack = vec![entry];
+        while let Some(beam) = stack.pop() {
+            let segment = &segments[&beam];
+            segment.tiles.iter().for_each(|tile| energized[*tile] = true);
+            stack.extend(segment.next.iter().filter(|next| seen.insert(**next)));
+        }
+        energized.values().filter(|energized| **energized).count()
+    }
 
-        let mut result = 0;
-        let tile = &mut self[&position];
-        if !tile.is_energized() {
-            result += 1;
+    /// Segments starting at `starts` and every segment reachable from them.
+    fn segments(&self, starts: impl IntoIterator<Item = Beam>) -> HashMap<Beam, Segment> {
+        let mut segments = HashMap::new();
+        let mut stack = starts
+            .into_iter()
+            .filter(|(position, _)| self.0.contains(*position))
+            .collect::<Vec<_>>();
+        while let Some(start) = stack.pop() {
+            if segments.contains_key(&start) {
+                continue;
+            }
+            let segment = self.segment(start);
+            stack.extend(segment.next.iter().copied());
+            segments.insert(start, segment);
         }
-        tile.energize();
-
-        match tile.next(&current_source_direction) {
-            (direction1, None) => position
-                .move_in_direction(&direction1, self)
-                .map_or(result, |pos| result + self.traverse(pos, direction1, cache)),
-            (direction1, Some(direction2)) => {
-                match (
-                    position.move_in_direction(&direction1, self),
-                    position.move_in_direction(&direction2, self),
-                ) {
-                    (Some(pos1), Some(pos2)) => {
-                        result + self.traverse(pos1, direction1, cache) + self.traverse(pos2, direction2, cache)
+        segments
+    }
+
+    fn segment(&self, start: Beam) -> Segment {
+        let (mut position, mut direction) = start;
+        let mut tiles = Vec::new();
+        loop {
+            tiles.push(position);
+            match self.0[position].next(&direction) {
+                (next, None) => direction = next,
+                (direction1, Some(direction2)) => {
+                    let next = [direction1, direction2]
+                        .into_iter()
+                        .filter_map(|direction| Some((self.0.neighbour(position, direction)?, direction)))
+                        .collect();
+                    return Segment { tiles, next };
+                }
+            }
+            // Without splitting every beam has a single predecessor, so the only beam a segment can loop back to is
+            // its start.
+            match self.0.neighbour(position, direction) {
+                Some(next) if (next, direction) != start => position = next,
+                _ => {
+                    return Segment {
+                        tiles,
+                        next: Vec::new(),
                     }
-                    (Some(pos1), None) => self.traverse(pos1, direction1, cache),
-                    (None, Some(pos2)) => result + self.traverse(pos2, direction2, cache),
-                    (None, None) => result,
                 }
             }
         }
     }
-}
 
-impl std::ops::IndexMut<&Coord> for Grid {
-    fn index_mut(&mut self, index: &Coord) -> &mut Tile {
-        &mut self.0[index.y][index.x]
+    /// Draws the tiles with the beams as arrows on top, tiles with a `heat` above 0 are highlighted when `colored`.
+    pub fn render_beams(&self, beams: &[(Coord, Direction)], heat: &Grid<u8>, colored: bool) -> String {
+        let mut arrows = Grid::new(self.0.width(), self.0.height(), None);
+        for (position, direction) in beams {
+            arrows[*position] = Some(direction.arrow());
+        }
+
+        self.render(|coord, tile| match (arrows[coord], colored) {
+            (Some(arrow), true) => arrow.to_string().bright_yellow().bold().to_string(),
+            (Some(arrow), false) => arrow.to_string(),
+            (None, true) if heat[coord] > 0 => tile.symbol().to_string().yellow().to_string(),
+            (None, true) => tile.symbol().to_string().dimmed().to_string(),
+            (None, false) => tile.symbol().to_string(),
+        })
     }
-}
 
-impl std::ops::Index<&Coord> for Grid {
-    type Output = Tile;
+    /// Draws how many directions beams crossed every tile in, as colors when `colored` and as digits otherwise.
+    pub fn render_heatmap(&self, heat: &Grid<u8>, colored: bool) -> String {
+        self.render(|coord, tile| {
+            let symbol = tile.symbol().to_string();
+            match (heat[coord], colored) {
+                (0, true) => symbol.dimmed().to_string(),
+                (0, false) => symbol,
+                (heat, false) => heat.to_string(),
+                (1, true) => symbol.yellow().to_string(),
+                (2, true) => symbol.bright_yellow().bold().to_string(),
+                (3, true) => symbol.red().bold().to_string(),
+                (_, true) => symbol.bright_red().bold().to_string(),
+            }
+        })
+    }
 
-    fn index(&self, index: &Coord) -> &Self::Output {
-        &self.0[index.y][index.x]
+    fn render(&self, mut cell: impl FnMut(Coord, &Tile) -> String) -> String {
+        let mut res = String::new();
+        for (coord, tile) in self.0.iter() {
+            if coord.x == 0 && coord.y > 0 {
+                res.push('\n');
+            }
+            res.push_str(&cell(coord, tile));
+        }
+        res
     }
 }
 
-impl FromStr for Grid {
+impl FromStr for Contraption {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        Ok(Grid(
-            s.lines()
-                .map(|line| line.chars().map(|c| c.try_into()).try_collect())
-                .try_collect()?,
-        ))
+        Ok(Contraption(s.parse()?))
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
 #[derive(Debug, Clone, Copy)]
 enum Tile {
-    MirrorUp { energized: bool },
-    MirrorDown { energized: bool },
-    SplitterVertical { energized: bool },
-    SplitterHorizontal { energized: bool },
-    Empty { energized: bool },
+    MirrorUp,
+    MirrorDown,
+    SplitterVertical,
+    SplitterHorizontal,
+    Empty,
 }
 
 impl Tile {
     fn next(&self, source_direction: &Direction) -> (Direction, Option<Direction>) {
         match (self, source_direction) {
-            (Tile::MirrorUp { .. }, Direction::Up) => (Direction::Right, None),
-            (Tile::MirrorUp { .. }, Direction::Down) => (Direction::Left, None),
-            (Tile::MirrorUp { .. }, Direction::Left) => (Direction::Down, None),
-            (Tile::MirrorUp { .. }, Direction::Right) => (Direction::Up, None),
-            (Tile::MirrorDown { .. }, Direction::Up) => (Direction::Left, None),
-            (Tile::MirrorDown { .. }, Direction::Down) => (Direction::Right, None),
-            (Tile::MirrorDown { .. }, Direction::Left) => (Direction::Up, None),
-            (Tile::MirrorDown { .. }, Direction::Right) => (Direction::Down, None),
-            (Tile::SplitterVertical { .. }, Direction::Up) => (Direction::Up, None),
-            (Tile::SplitterVertical { .. }, Direction::Down) => (Direction::Down, None),
-            (Tile::SplitterVertical { .. }, Direction::Left) => (Direction::Up, Some(Direction::Down)),
-            (Tile::SplitterVertical { .. }, Direction::Right) => (Direction::Up, Some(Direction::Down)),
-            (Tile::SplitterHorizontal { .. }, Direction::Up) => (Direction::Left, Some(Direction::Right)),
-            (Tile::SplitterHorizontal { .. }, Direction::Down) => (Direction::Left, Some(Direction::Right)),
-            (Tile::SplitterHorizontal { .. }, Direction::Left) => (Direction::Left, None),
-            (Tile::SplitterHorizontal { .. }, Direction::Right) => (Direction::Right, None),
+            (Tile::MirrorUp, Direction::Up) => (Direction::Right, None),
+            (Tile::MirrorUp, Direction::Down) => (Direction::Left, None),
+            (Tile::MirrorUp, Direction::Left) => (Direction::Down, None),
+            (Tile::MirrorUp, Direction::Right) => (Direction::Up, None),
+            (Tile::MirrorDown, Direction::Up) => (Direction::Left, None),
+            (Tile::MirrorDown, Direction::Down) => (Direction::Right, None),
+            (Tile::MirrorDown, Direction::Left) => (Direction::Up, None),
+            (Tile::MirrorDown, Direction::Right) => (Direction::Down, None),
+            (Tile::SplitterVertical, Direction::Up) => (Direction::Up, None),
+            (Tile::SplitterVertical, Direction::Down) => (Direction::Down, None),
+            (Tile::SplitterVertical, Direction::Left) => (Direction::Up, Some(Direction::Down)),
+            (Tile::SplitterVertical, Direction::Right) => (Direction::Up, Some(Direction::Down)),
+            (Tile::SplitterHorizontal, Direction::Up) => (Direction::Left, Some(Direction::Right)),
+            (Tile::SplitterHorizontal, Direction::Down) => (Direction::Left, Some(Direction::Right)),
+            (Tile::SplitterHorizontal, Direction::Left) => (Direction::Left, None),
+            (Tile::SplitterHorizontal, Direction::Right) => (Direction::Right, None),
             _ => (*source_direction, None),
         }
     }
 
-    fn energize(&mut self) {
-        match self {
-            Tile::MirrorUp { energized: has_beam } => *has_beam = true,
-            Tile::MirrorDown { energized: has_beam } => *has_beam = true,
-            Tile::SplitterVertical { energized: has_beam } => *has_beam = true,
-            Tile::SplitterHorizontal { energized: has_beam } => *has_beam = true,
-            Tile::Empty { energized: has_beam } => *has_beam = true,
-        }
-    }
-
-    fn is_energized(&self) -> bool {
+    fn symbol(&self) -> char {
         match self {
-            Tile::MirrorUp { energized: has_beam } => *has_beam,
-            Tile::MirrorDown { energized: has_beam } => *has_beam,
-            Tile::SplitterVertical { energized: has_beam } => *has_beam,
-            Tile::SplitterHorizontal { energized: has_beam } => *has_beam,
-            Tile::Empty { energized: has_beam } => *has_beam,
+            Tile::MirrorUp => '/',
+            Tile::MirrorDown => '\\',
+            Tile::SplitterVertical => '|',
+            Tile::SplitterHorizontal => '-',
+            Tile::Empty => '.',
         }
     }
 }
@@ -206,12 +375,101 @@ impl TryFrom<char> for Tile {
     type Error = anyhow::Error;
     fn try_from(s: char) -> Result<Self, Self::Error> {
         match s {
-            '|' => Ok(Tile::SplitterVertical { energized: false }),
-            '-' => Ok(Tile::SplitterHorizontal { energized: false }),
-            '/' => Ok(Tile::MirrorUp { energized: false }),
-            '\\' => Ok(Tile::MirrorDown { energized: false }),
-            '.' => Ok(Tile::Empty { energized: false }),
+            '|' => Ok(Tile::SplitterVertical),
+            '-' => Ok(Tile::SplitterHorizontal),
+            '/' => Ok(Tile::MirrorUp),
+            '\\' => Ok(Tile::MirrorDown),
+            '.' => Ok(Tile::Empty),
             _ => Err(anyhow::anyhow!("Invalid tile: {}", s)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Contraption, Coord, Direction, Entry, Grid, Tile};
+
+    const SAMPLE: &str = r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....
+";
+
+    crate::sample_tests!(super::Day16 {
+        part1_sample: part1(SAMPLE) == 46,
+        part2_sample: part2(SAMPLE) == 51,
+    });
+
+    #[test]
+    fn propagation_heatmap() {
+        let contraption: Contraption = SAMPLE.parse().unwrap();
+        let propagation = contraption.propagate(Coord { x: 0, y: 0 }, Direction::Right);
+        assert_eq!(propagation.energized(), 46);
+        assert_eq!(propagation.steps[0], [(Coord { x: 0, y: 0 }, Direction::Right)]);
+        let expected = r"121111....
+|1-.\1....
+.1...11111
+.1...11.|.
+.1...11...
+.1...11..\
+.1..1221..
+12111111..
+.1111211.\
+.1//.1.1..";
+        assert_eq!(contraption.render_heatmap(&propagation.heat, false), expected);
+    }
+
+    #[test]
+    fn best_entry_of_the_sample() {
+        let contraption: Contraption = SAMPLE.parse().unwrap();
+        let expected = Entry {
+            position: Coord { x: 3, y: 0 },
+            direction: Direction::Down,
+            energized: 51,
+        };
+        assert_eq!(contraption.best_entry(1), Some(expected));
+        assert_eq!(contraption.best_entry(4), Some(expected));
+    }
+
+    #[test]
+    fn rectangular_contraption() {
+        let contraption: Contraption = SAMPLE
+            .lines()
+            .skip(3)
+            .take(5)
+            .collect::<Vec<_>>()
+            .join("\n")
+            .parse()
+            .unwrap();
+        for (position, direction) in contraption.entries() {
+            assert_eq!(
+                contraption.energized(position, direction),
+                contraption.propagate(position, direction).energized()
+            );
+        }
+        let best = contraption
+            .entries()
+            .map(|(position, direction)| contraption.propagate(position, direction).energized())
+            .max();
+        assert_eq!(best, Some(13));
+        assert_eq!(contraption.best_entry(3).map(|entry| entry.energized), best);
+        assert_eq!(contraption.entries().count(), 2 * (10 + 5));
+    }
+
+    #[test]
+    fn empty_contraption() {
+        for contraption in [
+            Contraption(Grid::new(0, 0, Tile::Empty)),
+            Contraption(Grid::new(0, 3, Tile::Empty)),
+        ] {
+            assert_eq!(contraption.entries().count(), 0);
+            assert_eq!(contraption.best_entry(2), None);
+        }
+    }
+}
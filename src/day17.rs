@@ -1,233 +1,344 @@
 use anyhow::{Context, Result};
-use itertools::Itertools;
-use std::{
-    collections::{BinaryHeap, HashMap},
-    str::FromStr,
+use colored::Colorize;
+use std::{collections::BTreeSet, io::Write, str::FromStr};
+
+use crate::{
+    answer::Answer,
+    grid::{Coord, Direction, Grid},
+    search,
+    solution::{Metadata, Part, Solution},
 };
 
-pub fn part1(input: &str) -> Result<i64> {
-    let mut graph = input.parse::<Graph>()?;
-    let start = Coord { x: 0, y: 0 };
-    let end = Coord {
-        x: graph.0.last().unwrap().len() - 1,
-        y: graph.0.len() - 1,
-    };
+pub struct Day17;
 
-    Ok(graph.solve(start, end, 0, 3) / 2)
-}
+impl Solution for Day17 {
+    type Input = Graph;
 
-pub fn part2(input: &str) -> Result<i64> {
-    let mut graph = input.parse::<Graph>()?;
-    let start = Coord { x: 0, y: 0 };
-    let end = Coord {
-        x: graph.0.last().unwrap().len() - 1,
-        y: graph.0.len() - 1,
+    const METADATA: Metadata = Metadata {
+        day: 17,
+        title: "Clumsy Crucible",
     };
 
-    Ok(graph.solve(start, end, 4, 10) / 2)
-}
-
-#[rustfmt::skip]
-impl Graph {
-    fn solve(&mut self, start: Coord, end: Coord, min_steps: i64, max_steps: i64) -> i64 {
-        let mut visited = HashMap::new();
-        let mut stack = BinaryHeap::new();
-
-        visited.insert(VisitedKey { position: start, direction: Direction::Right, steps_in_direction: 0 }, 0);
-        visited.insert(VisitedKey { position: start, direction: Direction::Down, steps_in_direction: 0, }, 0);
-        stack.push(Frame { position: start, direction: Direction::Right, cost: 0, steps_in_direction: 0 });
-        while let Some(Frame { direction, position, cost, steps_in_direction }) = stack.pop()
-        {
-            if position == end {
-                return cost;
-            }
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
 
-            let dist_key = VisitedKey {
-                position,
-                direction,
-                steps_in_direction,
-            };
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
 
-            if visited.contains_key(&dist_key) && visited[&dist_key] < cost {
-                continue;
-            }
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
 
-            for c in [
-                (position.above(self), Direction::Up),
-                (position.right(self), Direction::Right),
-                (position.below(self), Direction::Down),
-                (position.left(self), Direction::Left),
-            ]
-            .iter()
-            .filter(|c| direction != c.1.opposite())
-            .filter_map(|(pos, dir)| pos.map(|p| (p, dir)))
-            {
-                let (new_pos, direction2) = c;
-
-                let cost = cost + self[new_pos];
-
-                let next_frame = Frame {
-                    position: new_pos,
-                    direction: *direction2,
-                    cost: cost + self[new_pos],
-                    steps_in_direction: if direction == *direction2 {
-                        steps_in_direction + 1
-                    } else {
-                        1
-                    },
-                };
-
-                let dist_key = VisitedKey {
-                    position: next_frame.position,
-                    direction: next_frame.direction,
-                    steps_in_direction: next_frame.steps_in_direction,
-                };
-
-                if (direction == *direction2 || steps_in_direction >= min_steps)
-                    && next_frame.steps_in_direction <= max_steps
-                    && (!visited.contains_key(&dist_key) || next_frame.cost < visited[&dist_key])
-                {
-                    stack.push(next_frame);
-                    visited.insert(dist_key, next_frame.cost);
-                }
-            }
-        }
-        unreachable!()
+    fn visualize(input: &Self::Input, part: Part, out: &mut dyn Write) -> Result<bool> {
+        let route = route(input, part)?;
+        writeln!(out, "Heat loss {} over {} blocks", route.heat_loss, route.moves.len())?;
+        writeln!(
+            out,
+            "{}",
+            input.render(&route, colored::control::SHOULD_COLORIZE.should_colorize())
+        )?;
+        Ok(true)
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct VisitedKey {
-    direction: Direction,
-    position: Coord,
-    steps_in_direction: i64,
+pub fn part1(graph: &Graph) -> Result<i64> {
+    Ok(route(graph, Part::One)?.heat_loss)
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Frame {
-    direction: Direction,
-    position: Coord,
-    cost: i64,
-    steps_in_direction: i64,
+pub fn part2(graph: &Graph) -> Result<i64> {
+    Ok(route(graph, Part::Two)?.heat_loss)
 }
 
-impl std::cmp::Ord for Frame {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other
-            .cost
-            .cmp(&self.cost)
-            .then_with(|| self.position.cmp(&other.position))
-    }
+/// Route of the regular crucible for part 1 and of the ultra crucible for part 2.
+fn route(graph: &Graph, part: Part) -> Result<Route> {
+    let rules = match part {
+        Part::One => CrucibleRules::crucible(graph),
+        Part::Two => CrucibleRules::ultra_crucible(graph),
+    };
+    graph
+        .route(&rules)
+        .with_context(|| format!("No path from {} to any of {:?}", rules.start, rules.goals))
 }
 
-impl std::cmp::PartialOrd for Frame {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
+/// How a crucible may move through the [`Graph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrucibleRules {
+    /// Blocks to move in a straight line before the crucible can turn.
+    pub min_steps: usize,
+    /// Blocks the crucible can move in a straight line at most.
+    pub max_steps: usize,
+    pub start: Coord,
+    /// Blocks the crucible may stop at, a route ends at the first one reached.
+    pub goals: BTreeSet<Coord>,
+    /// Whether the crucible can stop at a goal before moving `min_steps` in a straight line.
+    pub stop_below_min_steps: bool,
 }
 
-impl From<Frame> for VisitedKey {
-    fn from(f: Frame) -> Self {
+impl CrucibleRules {
+    /// Regular crucible going from the top left to the bottom right corner.
+    pub fn crucible(graph: &Graph) -> Self {
         Self {
-            direction: f.direction,
-            position: f.position,
-            steps_in_direction: f.steps_in_direction,
+            min_steps: 0,
+            max_steps: 3,
+            start: Coord { x: 0, y: 0 },
+            goals: BTreeSet::from([graph.bottom_right()]),
+            stop_below_min_steps: false,
         }
     }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
-    Up,
-    Right,
-    Down,
-    Left,
-}
 
-impl Direction {
-    fn opposite(&self) -> Self {
-        match self {
-            Self::Up => Self::Down,
-            Self::Right => Self::Left,
-            Self::Down => Self::Up,
-            Self::Left => Self::Right,
+    /// Ultra crucible going from the top left to the bottom right corner.
+    pub fn ultra_crucible(graph: &Graph) -> Self {
+        Self {
+            min_steps: 4,
+            max_steps: 10,
+            ..Self::crucible(graph)
         }
     }
+
+    fn can_stop(&self, crucible: &Crucible) -> bool {
+        crucible.steps >= self.min_steps || self.stop_below_min_steps
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Ord, PartialOrd)]
-struct Coord {
-    x: usize,
-    y: usize,
+/// Blocks a crucible moved through, without the starting block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    pub heat_loss: i64,
+    /// Every block entered together with the direction it was entered in.
+    pub moves: Vec<(Coord, Direction)>,
 }
 
-impl Coord {
-    fn above(&self, _g: &Graph) -> Option<Self> {
-        if self.y == 0 {
-            return None;
-        }
-        Some(Self {
-            x: self.x,
-            y: self.y - 1,
-        })
+impl Graph {
+    pub fn width(&self) -> usize {
+        self.0.width()
     }
-    fn right(&self, g: &Graph) -> Option<Self> {
-        if self.x == g.0[self.y].len() - 1 {
-            return None;
+
+    pub fn height(&self) -> usize {
+        self.0.height()
+    }
+
+    pub fn bottom_right(&self) -> Coord {
+        Coord {
+            x: self.0.width().saturating_sub(1),
+            y: self.0.height().saturating_sub(1),
         }
-        Some(Self {
-            x: self.x + 1,
-            y: self.y,
-        })
     }
-    fn below(&self, g: &Graph) -> Option<Self> {
-        if self.y == g.0.len() - 1 {
+
+    /// Route losing the least heat from the start to any of the goals, `None` when no goal can be reached.
+    pub fn route(&self, rules: &CrucibleRules) -> Option<Route> {
+        if !self.0.contains(rules.start) {
             return None;
         }
-        Some(Self {
-            x: self.x,
-            y: self.y + 1,
+
+        // Every block entered loses at least the least heat of any block, which may be 0, so the distance to the
+        // closest goal scaled by it never overestimates.
+        let min_heat_loss = self.0.values().copied().min().unwrap_or_default();
+        let path = search::astar(
+            self.starts(rules),
+            |crucible| self.successors(crucible, rules),
+            |crucible| {
+                let distance = rules
+                    .goals
+                    .iter()
+                    .map(|goal| crucible.position.manhattan_distance(*goal))
+                    .min();
+                distance.unwrap_or_default() as i64 * min_heat_loss
+            },
+            |crucible| rules.goals.contains(&crucible.position) && rules.can_stop(crucible),
+        )?;
+        Some(Route {
+            heat_loss: path.cost,
+            moves: path.states[1..].iter().map(|c| (c.position, c.direction)).collect(),
         })
     }
-    fn left(&self, _g: &Graph) -> Option<Self> {
-        if self.x == 0 {
-            return None;
+
+    /// Least heat lost reaching every block from the start and stopping there, ignoring the goals of `rules`.
+    pub fn heat_loss_map(&self, rules: &CrucibleRules) -> Grid<Option<i64>> {
+        let mut res = Grid::new(self.0.width(), self.0.height(), None);
+        if !self.0.contains(rules.start) {
+            return res;
         }
-        Some(Self {
-            x: self.x - 1,
-            y: self.y,
-        })
+
+        let costs = search::dijkstra_all(self.starts(rules), |crucible| self.successors(crucible, rules));
+        for (crucible, heat_loss) in costs {
+            if !rules.can_stop(&crucible) && crucible.position != rules.start {
+                continue;
+            }
+            let best = &mut res[crucible.position];
+            *best = Some(best.map_or(heat_loss, |best: i64| best.min(heat_loss)));
+        }
+        res
     }
-}
 
-struct Graph(Vec<Vec<i64>>);
+    /// Draws the heat loss of every block with the route on top as arrows, colored for a terminal when `colored`.
+    pub fn render(&self, route: &Route, colored: bool) -> String {
+        let mut arrows = Grid::new(self.0.width(), self.0.height(), None);
+        for (position, direction) in &route.moves {
+            arrows[*position] = Some(direction.arrow());
+        }
 
-impl std::ops::Index<Coord> for Graph {
-    type Output = i64;
+        let mut res = String::new();
+        for (coord, heat_loss) in self.0.iter() {
+            if coord.x == 0 && coord.y > 0 {
+                res.push('\n');
+            }
+            let cell = match (arrows[coord], colored) {
+                (Some(arrow), true) => arrow.to_string().bright_yellow().bold().to_string(),
+                (Some(arrow), false) => arrow.to_string(),
+                (None, true) => heat_loss.to_string().dimmed().to_string(),
+                (None, false) => heat_loss.to_string(),
+            };
+            res.push_str(&cell);
+        }
+        res
+    }
 
-    fn index(&self, index: Coord) -> &Self::Output {
-        &self.0[index.y][index.x]
+    fn starts(&self, rules: &CrucibleRules) -> [Crucible; 4] {
+        Direction::ALL.map(|direction| Crucible {
+            position: rules.start,
+            direction,
+            steps: 0,
+        })
     }
-}
 
-impl std::ops::IndexMut<Coord> for Graph {
-    fn index_mut(&mut self, index: Coord) -> &mut Self::Output {
-        &mut self.0[index.y][index.x]
+    fn successors(&self, crucible: &Crucible, rules: &CrucibleRules) -> Vec<(Crucible, i64)> {
+        self.0
+            .neighbours4(crucible.position)
+            .filter(|(direction, _)| *direction != crucible.direction.opposite())
+            .filter_map(|(direction, position)| {
+                let straight = direction == crucible.direction;
+                let steps = if straight { crucible.steps + 1 } else { 1 };
+                ((straight || crucible.steps >= rules.min_steps) && steps <= rules.max_steps).then(|| {
+                    let next = Crucible {
+                        position,
+                        direction,
+                        steps,
+                    };
+                    (next, self.0[position])
+                })
+            })
+            .collect()
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Crucible {
+    position: Coord,
+    direction: Direction,
+    /// Blocks moved in `direction` without turning.
+    steps: usize,
+}
+
+pub struct Graph(Grid<i64>);
+
 impl FromStr for Graph {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        Ok(Graph(
-            s.lines()
-                .map(|line| {
-                    line.chars()
-                        .map(|s| s.to_digit(10).map(|v| v.try_into().unwrap()).context("Invalid digit"))
-                        .try_collect()
-                })
-                .try_collect()?,
-        ))
+        Ok(Graph(Grid::parse_with(s, |coord, c| {
+            c.to_digit(10)
+                .map(i64::from)
+                .with_context(|| format!("Invalid digit '{}' at {}", c, coord))
+        })?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Coord, CrucibleRules, Graph};
+
+    const SAMPLE: &str = "\
+2413432311323
+3215453535623
+3255245654254
+3446585845452
+4546657867536
+1438598798454
+4457876987766
+3637877979653
+4654967986887
+4564679986453
+1224686865563
+2546548887735
+4322674655533
+";
+    const SAMPLE_ULTRA: &str = "\
+111111111111
+999999999991
+999999999991
+999999999991
+999999999991
+";
+
+    const SAMPLE_ULTRA_DOWN: &str = "\
+19999
+19999
+19999
+19999
+19999
+19999
+19999
+19999
+19999
+19999
+19999
+11111
+";
+
+    // The winding corridor of 0s is 6 blocks longer than cutting through a 1.
+    const ZERO_HEAT_LOSS: &str = "\
+0000
+1110
+0000
+0111
+0000
+";
+
+    crate::sample_tests!(super::Day17 {
+        part1_sample: part1(SAMPLE) == 102,
+        part2_sample: part2(SAMPLE) == 94,
+        part2_sample_ultra: part2(SAMPLE_ULTRA) == 71,
+        part2_sample_ultra_down: part2(SAMPLE_ULTRA_DOWN) == 71,
+        part1_zero_heat_loss: part1(ZERO_HEAT_LOSS) == 0,
+    });
+
+    #[test]
+    fn ultra_crucible_route() {
+        let graph: Graph = SAMPLE_ULTRA.parse().unwrap();
+        let route = graph.route(&CrucibleRules::ultra_crucible(&graph)).unwrap();
+        let expected = "\
+1>>>>>>>1111
+9999999v9991
+9999999v9991
+9999999v9991
+9999999v>>>>";
+        assert_eq!(graph.render(&route, false), expected);
+    }
+
+    #[test]
+    fn stopping_below_min_steps_at_the_goal() {
+        let graph: Graph = SAMPLE_ULTRA.parse().unwrap();
+        let rules = CrucibleRules {
+            stop_below_min_steps: true,
+            ..CrucibleRules::ultra_crucible(&graph)
+        };
+        assert_eq!(graph.route(&rules).unwrap().heat_loss, 47);
+    }
+
+    #[test]
+    fn heat_loss_map_agrees_with_routes() {
+        let graph: Graph = SAMPLE.parse().unwrap();
+        let rules = CrucibleRules::crucible(&graph);
+        let map = graph.heat_loss_map(&rules);
+        assert_eq!(map[rules.start], Some(0));
+        assert_eq!(map[graph.bottom_right()], Some(102));
+
+        let middle = CrucibleRules {
+            goals: [Coord { x: 6, y: 6 }].into(),
+            ..rules
+        };
+        assert_eq!(map[Coord { x: 6, y: 6 }], Some(graph.route(&middle).unwrap().heat_loss));
     }
 }
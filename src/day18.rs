@@ -5,12 +5,39 @@ use std::{
     str::FromStr,
 };
 
-pub fn part1(input: &str) -> Result<i64> {
-    let plan = input.parse::<Plan>()?;
+use crate::{
+    answer::Answer,
+    solution::{Metadata, Solution},
+};
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Plan;
+
+    const METADATA: Metadata = Metadata {
+        day: 18,
+        title: "Lavaduct Lagoon",
+    };
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+}
+
+pub fn part1(plan: &Plan) -> Result<i64> {
     let mut current = Coord { x: 0, y: 0 };
     let mut lagoon = Vec::new();
     let mut result = HashMap::new();
-    for dig in plan.0 {
+    for dig in plan.0.iter() {
         current = dig.commit(current, &mut lagoon);
     }
 
@@ -54,12 +81,11 @@ pub fn part1(input: &str) -> Result<i64> {
 }
 
 // shoelace formula
-pub fn part2(input: &str) -> Result<isize> {
-    let plan = input.parse::<Plan>()?;
+pub fn part2(plan: &Plan) -> Result<isize> {
     let mut current = Coord { x: 0, y: 0 };
     let mut lagoon = VecDeque::new();
-    for dig in plan.0 {
-        let dig = dig.into_color_dig();
+    for dig in plan.0.iter() {
+        let dig = dig.to_color_dig();
         let (mut x, mut y) = (current.x, current.y);
         match dig.direction {
             Direction::Up => y -= dig.len,
@@ -84,7 +110,7 @@ pub fn part2(input: &str) -> Result<isize> {
 }
 
 #[derive(Debug)]
-struct Plan(Vec<Dig>);
+pub struct Plan(Vec<Dig>);
 
 #[derive(Debug)]
 struct Edge {
@@ -149,8 +175,8 @@ impl Dig {
         }
         Coord { x, y }
     }
-    fn into_color_dig(self) -> Self {
-        let mut color = self.color;
+    fn to_color_dig(&self) -> Self {
+        let mut color = self.color.clone();
         let dir = match color.pop().unwrap() {
             '0' => Direction::Right,
             '1' => Direction::Down,
@@ -193,3 +219,28 @@ impl FromStr for Plan {
         Ok(Plan(s.lines().map(|l| l.parse()).try_collect()?))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE: &str = "\
+R 6 (#70c710)
+D 5 (#0dc571)
+L 2 (#5713f0)
+D 2 (#d2c081)
+R 2 (#59c680)
+D 2 (#411b91)
+L 5 (#8ceee2)
+U 2 (#caa173)
+L 1 (#1b58a2)
+U 2 (#caa171)
+R 2 (#7807d2)
+U 3 (#a77fa3)
+L 2 (#015232)
+U 2 (#7a21e3)
+";
+
+    crate::sample_tests!(super::Day18 {
+        part1_sample: part1(SAMPLE) == 62,
+        part2_sample: part2(SAMPLE) == 952408144115_i64,
+    });
+}
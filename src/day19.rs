@@ -1,30 +1,46 @@
 use anyhow::{bail, Context, Result};
 use itertools::Itertools;
-use std::{
-    collections::{HashMap, VecDeque},
-    str::FromStr,
+use std::{collections::HashMap, str::FromStr};
+
+use crate::{
+    answer::Answer,
+    solution::{Metadata, Solution},
 };
 
-pub fn part1(input: &str) -> Result<i64> {
-    let (workflows, parts) = input.split_once("\n\n").context("Invalid input format")?;
-    let workflows: HashMap<String, Vec<Rule>> = workflows
-        .lines()
-        .map(|w| -> Result<_> {
-            let w = w.parse::<Workflow>()?;
-            Ok((w.name, w.rules))
-        })
-        .try_collect()?;
-    let parts: Vec<Part> = parts.lines().map(|p| p.parse()).try_collect()?;
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = System;
+
+    const METADATA: Metadata = Metadata {
+        day: 19,
+        title: "Aplenty",
+    };
 
-    let sum = parts
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+}
+
+pub fn part1(system: &System) -> Result<i64> {
+    let sum = system
+        .parts
         .iter()
         .filter(|part| {
             let mut rule_name = "in";
             loop {
-                let Some(rules) = workflows.get(rule_name) else {
+                let Some(workflow) = system.workflows.get(rule_name) else {
                     return false;
                 };
-                for rule in rules {
+                for rule in &workflow.rules {
                     if part.matches(rule) {
                         match &rule.action {
                             Action::Accept => return true,
@@ -44,20 +60,36 @@ pub fn part1(input: &str) -> Result<i64> {
     Ok(sum)
 }
 
-pub fn part2(input: &str) -> Result<i64> {
-    let (workflows, _) = input.split_once("\n\n").context("Invalid input format")?;
-    let workflows: HashMap<String, Workflow> = workflows
-        .lines()
-        .map(|w| -> Result<_> {
-            let w = w.parse::<Workflow>()?;
-            Ok((w.name.clone(), w))
-        })
-        .try_collect()?;
-
-    workflows
+pub fn part2(system: &System) -> Result<i64> {
+    system
+        .workflows
         .get("in")
         .context("Invalid state. No initial WF.")?
-        .solve(&workflows, PartRange::default())
+        .solve(&system.workflows, PartRange::default())
+}
+
+#[derive(Debug)]
+pub struct System {
+    workflows: HashMap<String, Workflow>,
+    parts: Vec<Part>,
+}
+
+impl FromStr for System {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (workflows, parts) = s.split_once("\n\n").context("Invalid input format")?;
+        let workflows = workflows
+            .lines()
+            .map(|w| -> Result<_> {
+                let w = w.parse::<Workflow>()?;
+                Ok((w.name.clone(), w))
+            })
+            .try_collect()?;
+        let parts = parts.lines().map(|p| p.parse()).try_collect()?;
+
+        Ok(System { workflows, parts })
+    }
 }
 
 #[derive(Debug, Default)]
@@ -337,3 +369,31 @@ impl FromStr for Action {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE: &str = "\
+px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}
+
+{x=787,m=2655,a=1222,s=2876}
+{x=1679,m=44,a=2067,s=496}
+{x=2036,m=264,a=79,s=2244}
+{x=2461,m=1339,a=466,s=291}
+{x=2127,m=1623,a=2188,s=1013}
+";
+
+    crate::sample_tests!(super::Day19 {
+        part1_sample: part1(SAMPLE) == 19114,
+        part2_sample: part2(SAMPLE) == 167409079868000_i64,
+    });
+}
@@ -2,7 +2,35 @@ use std::str::FromStr;
 
 use anyhow::Result;
 
-pub fn part1(input: &str) -> Result<usize> {
+use crate::{
+    answer::Answer,
+    solution::{Metadata, Solution},
+};
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Game>;
+
+    const METADATA: Metadata = Metadata {
+        day: 2,
+        title: "Cube Conundrum",
+    };
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.lines().filter_map(|line| line.parse::<Game>().ok()).collect())
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+}
+
+pub fn part1(input: &[Game]) -> Result<usize> {
     let valid_game = Game {
         id: 0,
         green: 13,
@@ -11,8 +39,7 @@ pub fn part1(input: &str) -> Result<usize> {
     };
 
     let res: usize = input
-        .lines()
-        .filter_map(|line| line.parse::<Game>().ok())
+        .iter()
         .filter(|game| game.green <= valid_game.green && game.red <= valid_game.red && game.blue <= valid_game.blue)
         .map(|game| game.id)
         .sum();
@@ -20,18 +47,14 @@ pub fn part1(input: &str) -> Result<usize> {
     Ok(res)
 }
 
-pub fn part2(input: &str) -> Result<i32> {
-    let res: i32 = input
-        .lines()
-        .filter_map(|line| line.parse::<Game>().ok())
-        .map(|game| game.power())
-        .sum();
+pub fn part2(input: &[Game]) -> Result<i32> {
+    let res: i32 = input.iter().map(|game| game.power()).sum();
 
     Ok(res)
 }
 
 #[derive(Debug)]
-struct Game {
+pub struct Game {
     id: usize,
     green: i32,
     red: i32,
@@ -110,3 +133,19 @@ impl FromStr for Colors {
         Ok(res)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE: &str = "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
+";
+
+    crate::sample_tests!(super::Day2 {
+        part1_sample: part1(SAMPLE) == 8,
+        part2_sample: part2(SAMPLE) == 2286,
+    });
+}
@@ -6,8 +6,36 @@ use std::{
     str::FromStr,
 };
 
-pub fn part1(input: &str) -> Result<i64> {
-    let mut modules = input.parse::<Network>()?.modules;
+use crate::{
+    answer::Answer,
+    solution::{Metadata, Solution},
+};
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = Network;
+
+    const METADATA: Metadata = Metadata {
+        day: 20,
+        title: "Pulse Propagation",
+    };
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+}
+
+pub fn part1(network: &Network) -> Result<i64> {
+    let mut modules = network.modules.clone();
 
     for _ in 0..1000 {
         let mut queue = modules
@@ -33,8 +61,8 @@ pub fn part1(input: &str) -> Result<i64> {
     Ok((res.0 as i64 + 1000) * res.1 as i64)
 }
 
-pub fn part2(input: &str) -> Result<i64> {
-    let mut modules = input.parse::<Network>()?.modules;
+pub fn part2(network: &Network) -> Result<i64> {
+    let mut modules = network.modules.clone();
 
     let rx_input = modules
         .iter()
@@ -93,7 +121,7 @@ fn lcm(a: i64, b: i64) -> i64 {
     (a * b) / gcd(a, b)
 }
 
-#[derive(Debug, strum::EnumDiscriminants)]
+#[derive(Debug, Clone, strum::EnumDiscriminants)]
 enum Module {
     FlipFlop(FlipFlop),
     Conjunction(Conjuction),
@@ -186,7 +214,7 @@ impl Signal {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct FlipFlop {
     name: Rc<str>,
     outputs: Vec<Rc<str>>,
@@ -195,7 +223,7 @@ struct FlipFlop {
     high_sent: usize,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Conjuction {
     name: Rc<str>,
     outputs: Vec<Rc<str>>,
@@ -204,7 +232,7 @@ struct Conjuction {
     high_sent: usize,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Broadcast {
     name: Rc<str>,
     outputs: Vec<Rc<str>>,
@@ -213,7 +241,7 @@ struct Broadcast {
 }
 
 #[derive(Debug)]
-struct Network {
+pub struct Network {
     modules: HashMap<Rc<str>, Module>,
 }
 
@@ -274,3 +302,27 @@ impl FromStr for Module {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE: &str = "\
+broadcaster -> a, b, c
+%a -> b
+%b -> c
+%c -> inv
+&inv -> a
+";
+    const SAMPLE_OUTPUT: &str = "\
+broadcaster -> a
+%a -> inv, con
+&inv -> b
+%b -> con
+&con -> output
+";
+
+    // Part 2 has no sample, it depends on the `rx` module of the real input.
+    crate::sample_tests!(super::Day20 {
+        part1_sample: part1(SAMPLE) == 32000000,
+        part1_sample_output: part1(SAMPLE_OUTPUT) == 11687500,
+    });
+}
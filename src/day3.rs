@@ -1,4 +1,36 @@
+use std::str::FromStr;
+
 use anyhow::Result;
+use itertools::Itertools;
+
+use crate::{
+    answer::Answer,
+    grid::{Coord, Grid},
+    solution::{Metadata, Solution},
+};
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Schematic;
+
+    const METADATA: Metadata = Metadata {
+        day: 3,
+        title: "Gear Ratios",
+    };
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+}
 
 #[derive(Debug)]
 enum Mode {
@@ -6,19 +38,14 @@ enum Mode {
     GearRatio,
 }
 
-pub fn part1(input: &str) -> Result<i32> {
-    let mut res: Vec<Vec<Element>> = input
-        .lines()
-        .map(|line| line.chars().map(Element::new).collect())
-        .collect();
+pub fn part1(input: &Schematic) -> Result<i32> {
+    let mut grid = input.0.clone();
 
     let mut sum = 0;
-    for y in 0..res.len() {
-        for x in 0..res.first().unwrap().len() {
-            if res[y][x].is_symbol() {
-                if let Some(val) = sum_adjacents(&mut res, Point { x, y }, Mode::FindParts) {
-                    sum += val;
-                }
+    for coord in input.0.coords() {
+        if grid[coord].is_symbol() {
+            if let Some(val) = sum_adjacents(&mut grid, coord, Mode::FindParts) {
+                sum += val;
             }
         }
     }
@@ -26,19 +53,14 @@ pub fn part1(input: &str) -> Result<i32> {
     Ok(sum)
 }
 
-pub fn part2(input: &str) -> Result<i32> {
-    let mut res: Vec<Vec<Element>> = input
-        .lines()
-        .map(|line| line.chars().map(Element::new).collect())
-        .collect();
+pub fn part2(input: &Schematic) -> Result<i32> {
+    let mut grid = input.0.clone();
 
     let mut sum = 0;
-    for y in 0..res.len() {
-        for x in 0..res.first().unwrap().len() {
-            if res[y][x].value == '*' {
-                if let Some(val) = sum_adjacents(&mut res, Point { x, y }, Mode::GearRatio) {
-                    sum += val;
-                }
+    for coord in input.0.coords() {
+        if grid[coord].value == '*' {
+            if let Some(val) = sum_adjacents(&mut grid, coord, Mode::GearRatio) {
+                sum += val;
             }
         }
     }
@@ -46,78 +68,33 @@ pub fn part2(input: &str) -> Result<i32> {
     Ok(sum)
 }
 
-fn sum_adjacents(input: &mut Vec<Vec<Element>>, p: Point, mode: Mode) -> Option<i32> {
+#[derive(Debug, Clone)]
+pub struct Schematic(Grid<Element>);
+
+impl FromStr for Schematic {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Schematic(Grid::parse_with(s, |_, c| Ok(Element::new(c)))?))
+    }
+}
+
+fn sum_adjacents(grid: &mut Grid<Element>, coord: Coord, mode: Mode) -> Option<i32> {
     let mut sum = 0;
     let mut product = 1;
     let mut adjacents = 0;
 
-    let above = p.above();
-    if p.y > 0 && input[above.y][above.x].is_numeric() {
-        if let Some(num) = above.combine_with_adjacent_into_number(input) {
-            adjacents += 1;
-            sum += num;
-            product *= num;
-        }
-    }
-    let right = p.right();
-    if p.x < input.first().unwrap().len() - 1 && input[right.y][right.x].is_numeric() {
-        if let Some(num) = right.combine_with_adjacent_into_number(input) {
-            adjacents += 1;
-            sum += num;
-            product *= num;
+    for neighbour in grid.neighbours8(coord).collect_vec() {
+        if !grid[neighbour].is_numeric() {
+            continue;
         }
-    }
-    let below = p.below();
-    if p.y < input.len() - 1 && input[below.y][below.x].is_numeric() {
-        if let Some(num) = below.combine_with_adjacent_into_number(input) {
-            adjacents += 1;
-            sum += num;
-            product *= num;
-        }
-    }
-    let left = p.left();
-    if p.x > 0 && input[left.y][left.x].is_numeric() {
-        if let Some(num) = left.combine_with_adjacent_into_number(input) {
-            adjacents += 1;
-            sum += num;
-            product *= num;
-        }
-    }
-    let top_right = p.top_right();
-    if p.x < input.first().unwrap().len() - 1 && p.y > 0 && input[top_right.y][top_right.x].is_numeric() {
-        if let Some(num) = top_right.combine_with_adjacent_into_number(input) {
-            adjacents += 1;
-            sum += num;
-            product *= num;
-        }
-    }
-    let top_left = p.top_left();
-    if p.x > 0 && p.y > 0 && input[top_left.y][top_left.x].is_numeric() {
-        if let Some(num) = top_left.combine_with_adjacent_into_number(input) {
-            adjacents += 1;
-            sum += num;
-            product *= num;
-        }
-    }
-    let below_right = p.below_right();
-    if p.y < input.len() - 1
-        && p.x < input.first().unwrap().len() - 1
-        && input[below_right.y][below_right.x].is_numeric()
-    {
-        if let Some(num) = below_right.combine_with_adjacent_into_number(input) {
-            adjacents += 1;
-            sum += num;
-            product *= num;
-        }
-    }
-    let below_left = p.below_left();
-    if p.y < input.len() - 1 && p.x > 0 && input[below_left.y][below_left.x].is_numeric() {
-        if let Some(num) = below_left.combine_with_adjacent_into_number(input) {
+        if let Some(num) = combine_with_adjacent_into_number(grid.row_mut(neighbour.y), neighbour.x) {
             adjacents += 1;
             sum += num;
             product *= num;
         }
     }
+
     match mode {
         Mode::FindParts => Some(sum),
         Mode::GearRatio if adjacents != 2 => None,
@@ -125,102 +102,33 @@ fn sum_adjacents(input: &mut Vec<Vec<Element>>, p: Point, mode: Mode) -> Option<
     }
 }
 
-#[derive(Debug)]
-struct Point {
-    x: usize,
-    y: usize,
+/// Reads the whole number around `x`, `None` when a digit of it was already read before.
+fn combine_with_adjacent_into_number(row: &mut [Element], x: usize) -> Option<i32> {
+    let start = row[..x].iter().rposition(|e| !e.is_numeric()).map_or(0, |idx| idx + 1);
+    let end = row[x..]
+        .iter()
+        .position(|e| !e.is_numeric())
+        .map_or(row.len(), |idx| x + idx);
+
+    let digits = &mut row[start..end];
+    let was_already_handled = digits.iter().any(|e| e.handled);
+    digits.iter_mut().for_each(|e| e.handled = true);
+
+    if was_already_handled {
+        return None;
+    }
+
+    Some(
+        digits
+            .iter()
+            .map(|e| e.value)
+            .collect::<String>()
+            .parse::<i32>()
+            .unwrap(),
+    )
 }
 
-impl Point {
-    fn above(&self) -> Self {
-        Self {
-            x: self.x,
-            y: self.y - 1,
-        }
-    }
-    fn right(&self) -> Self {
-        Self {
-            x: self.x + 1,
-            y: self.y,
-        }
-    }
-    fn below(&self) -> Self {
-        Self {
-            x: self.x,
-            y: self.y + 1,
-        }
-    }
-    fn left(&self) -> Self {
-        Self {
-            x: self.x - 1,
-            y: self.y,
-        }
-    }
-    fn top_right(&self) -> Self {
-        Self {
-            x: self.x + 1,
-            y: self.y - 1,
-        }
-    }
-    fn top_left(&self) -> Self {
-        Self {
-            x: self.x - 1,
-            y: self.y - 1,
-        }
-    }
-    fn below_right(&self) -> Self {
-        Self {
-            x: self.x + 1,
-            y: self.y + 1,
-        }
-    }
-    fn below_left(&self) -> Self {
-        Self {
-            x: self.x - 1,
-            y: self.y + 1,
-        }
-    }
-
-    fn combine_with_adjacent_into_number(&self, input: &mut [Vec<Element>]) -> Option<i32> {
-        let mut x = self.x;
-        let y = self.y;
-        let mut res = Vec::new();
-        let mut was_already_handled = false;
-
-        while x != 0 && input[y][x].is_numeric() {
-            if input[y][x - 1].is_numeric() {
-                if input[y][x - 1].handled {
-                    was_already_handled = true;
-                }
-                input[y][x - 1].handled = true;
-                res.push(input[y][x - 1].value);
-            }
-            x -= 1;
-        }
-        res.reverse();
-        let mut x = self.x;
-        res.push(input[self.y][self.x].value);
-        input[y][x].handled = true;
-        while x < input.first().unwrap().len() - 1 && input[y][x].is_numeric() {
-            if input[y][x + 1].is_numeric() {
-                if input[y][x + 1].handled {
-                    was_already_handled = true;
-                }
-                input[y][x + 1].handled = true;
-                res.push(input[y][x + 1].value);
-            }
-            x += 1;
-        }
-
-        if was_already_handled {
-            return None;
-        }
-
-        Some(res.iter().collect::<String>().parse::<i32>().unwrap())
-    }
-}
-
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Element {
     handled: bool,
     value: char,
@@ -239,3 +147,24 @@ impl Element {
         self.value != '.' && !self.value.is_numeric()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE: &str = "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+";
+
+    crate::sample_tests!(super::Day3 {
+        part1_sample: part1(SAMPLE) == 4361,
+        part2_sample: part2(SAMPLE) == 467835,
+    });
+}
@@ -3,15 +3,39 @@ use std::{collections::VecDeque, str::FromStr};
 use anyhow::{bail, Result};
 use itertools::Itertools;
 
-pub fn part1(input: &str) -> Result<i32> {
-    input
-        .lines()
-        .map(|line| line.parse::<Card>())
-        .map(|card| -> Result<i32> { Ok(card?.count_points()) })
-        .try_fold(0, |acc, val| -> Result<i32> { Ok(acc + val?) })
+use crate::{
+    answer::Answer,
+    solution::{Metadata, Solution},
+};
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<Card>;
+
+    const METADATA: Metadata = Metadata {
+        day: 4,
+        title: "Scratchcards",
+    };
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.lines().map(|line| line.parse::<Card>()).try_collect()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+}
+
+pub fn part1(input: &[Card]) -> Result<i32> {
+    Ok(input.iter().map(Card::count_points).sum())
 }
-pub fn part2(input: &str) -> Result<i32> {
-    let original_cards: VecDeque<_> = input.lines().map(|line| line.parse::<Card>()).try_collect()?;
+pub fn part2(input: &[Card]) -> Result<i32> {
+    let original_cards = input;
     let mut processed_cards = 0;
 
     let mut current_cards: VecDeque<_> = (0..original_cards.len()).collect();
@@ -33,7 +57,7 @@ pub fn part2(input: &str) -> Result<i32> {
 }
 
 #[derive(Debug, Default, Clone)]
-struct Card {
+pub struct Card {
     idx: usize,
     winning_nums: Vec<i32>,
     rolled_nums: Vec<i32>,
@@ -84,3 +108,20 @@ impl Card {
             .count()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE: &str = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
+";
+
+    crate::sample_tests!(super::Day4 {
+        part1_sample: part1(SAMPLE) == 13,
+        part2_sample: part2(SAMPLE) == 30,
+    });
+}
@@ -4,8 +4,35 @@ use anyhow::{anyhow, bail, Context, Result};
 use itertools::Itertools;
 use strum::{EnumIter, IntoEnumIterator};
 
-pub fn part1(input: &str) -> Result<i64> {
-    let maps: Maps = input.parse()?;
+use crate::{
+    answer::Answer,
+    solution::{Metadata, Solution},
+};
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = Maps;
+
+    const METADATA: Metadata = Metadata {
+        day: 5,
+        title: "If You Give A Seed A Fertilizer",
+    };
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+}
+
+pub fn part1(maps: &Maps) -> Result<i64> {
     (0..maps.seeds.len())
         .fold(None, |acc, i| {
             let current = MapsEnum::iter().fold(maps.seeds[i], |acc, val| maps.get(val).map(acc));
@@ -18,8 +45,7 @@ pub fn part1(input: &str) -> Result<i64> {
         .ok_or(anyhow!("Error, no destination found at all"))
 }
 
-pub fn part2(input: &str) -> Result<i64> {
-    let maps: Maps = input.parse()?;
+pub fn part2(maps: &Maps) -> Result<i64> {
     let mut lowest_dest_location = None;
 
     for range in maps.seeds.iter().chunks(2).into_iter().map(|mut val| {
@@ -72,7 +98,7 @@ impl FromStr for MapsEnum {
 }
 
 #[derive(Debug, Default)]
-struct Maps {
+pub struct Maps {
     seeds: Vec<i64>,
     seed_to_soil: SeedMaps,
     soil_to_fertilizer: SeedMaps,
@@ -174,3 +200,47 @@ impl SeedMap {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE: &str = "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4
+";
+
+    crate::sample_tests!(super::Day5 {
+        part1_sample: part1(SAMPLE) == 35,
+        part2_sample: part2(SAMPLE) == 46,
+    });
+}
@@ -3,22 +3,62 @@ use std::str::FromStr;
 use anyhow::{Context, Result};
 use itertools::Itertools;
 
-pub fn part1(input: &str) -> Result<f64> {
-    Ok(input.parse::<Races>()?.0.iter().fold(1.0, |acc, val| {
-        let (x1, x2) = quadratic(1.0, -val.duration, val.record_distance);
-        acc * ((x2.ceil() - x1.floor()).abs() + 1.0)
-    }))
+use crate::{
+    answer::Answer,
+    solution::{Metadata, Solution},
+};
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = Sheet;
+
+    const METADATA: Metadata = Metadata {
+        day: 6,
+        title: "Wait For It",
+    };
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+}
+
+pub fn part1(input: &Sheet) -> Result<i64> {
+    Ok(count_ways_to_win(&input.races))
+}
+
+pub fn part2(input: &Sheet) -> Result<i64> {
+    Ok(count_ways_to_win(&input.single_race))
+}
+
+fn count_ways_to_win(races: &Races) -> i64 {
+    races.0.iter().map(Race::count_ways_to_win).product()
 }
 
-pub fn part2(input: &str) -> Result<f64> {
-    part1(&input.replace(' ', ""))
+/// The sheet read both as separate races and, with the spaces removed, as one long race.
+#[derive(Debug)]
+pub struct Sheet {
+    races: Races,
+    single_race: Races,
 }
 
-fn quadratic(a: f64, b: f64, c: f64) -> (f64, f64) {
-    let discr = b.powi(2) - 4.0 * a * c;
-    let x1 = (-b + discr.sqrt()) / (2.0 * a);
-    let x2 = (-b - discr.sqrt()) / (2.0 * a);
-    (x1, x2)
+impl FromStr for Sheet {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Sheet {
+            races: s.parse()?,
+            single_race: s.replace(' ', "").parse()?,
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -26,8 +66,29 @@ struct Races(Vec<Race>);
 
 #[derive(Debug)]
 struct Race {
-    duration: f64,
-    record_distance: f64,
+    duration: i64,
+    record_distance: i64,
+}
+
+impl Race {
+    fn distance(&self, hold: i64) -> i64 {
+        hold * (self.duration - hold)
+    }
+
+    /// The distance grows until half of the duration and is symmetric, so the winning hold times are the range
+    /// between the first one beating the record and its mirror.
+    fn count_ways_to_win(&self) -> i64 {
+        let (mut low, mut high) = (0, self.duration / 2 + 1);
+        while low < high {
+            let mid = (low + high) / 2;
+            if self.distance(mid) > self.record_distance {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        (self.duration - 2 * low + 1).max(0)
+    }
 }
 
 impl FromStr for Races {
@@ -55,11 +116,24 @@ impl FromStr for Races {
                 .zip(second.split_whitespace())
                 .map(|(time, distance)| -> Result<Race> {
                     Ok(Race {
-                        duration: time.parse::<f64>()?,
-                        record_distance: distance.parse::<f64>()?,
+                        duration: time.parse()?,
+                        record_distance: distance.parse()?,
                     })
                 })
                 .try_collect()?,
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE: &str = "\
+Time:      7  15   30
+Distance:  9  40  200
+";
+
+    crate::sample_tests!(super::Day6 {
+        part1_sample: part1(SAMPLE) == 288,
+        part2_sample: part2(SAMPLE) == 71503,
+    });
+}
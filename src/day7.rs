@@ -1,24 +1,69 @@
+use std::str::FromStr;
+
 use anyhow::{anyhow, bail, Context, Result};
 use itertools::Itertools;
 
-pub fn part1(input: &str) -> Result<usize> {
-    Ok(input
-        .lines()
-        .map(|line| Part1(line).try_into().expect("Parsing to always succeed"))
-        .sorted()
-        .rev()
-        .enumerate()
-        .fold(0, |acc, (idx, hand): (usize, HandOfCards)| acc + hand.bid * (idx + 1)))
+use crate::{
+    answer::Answer,
+    solution::{Metadata, Solution},
+};
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Hands;
+
+    const METADATA: Metadata = Metadata {
+        day: 7,
+        title: "Camel Cards",
+    };
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
 }
 
-pub fn part2(input: &str) -> Result<usize> {
-    Ok(input
-        .lines()
-        .map(|line| Part2(line).try_into().expect("Parsing to always succeed"))
+pub fn part1(input: &Hands) -> Result<usize> {
+    Ok(total_winnings(&input.regular))
+}
+
+pub fn part2(input: &Hands) -> Result<usize> {
+    Ok(total_winnings(&input.with_jokers))
+}
+
+fn total_winnings(hands: &[HandOfCards]) -> usize {
+    hands
+        .iter()
         .sorted()
         .rev()
         .enumerate()
-        .fold(0, |acc, (idx, hand): (usize, HandOfCards)| acc + hand.bid * (idx + 1)))
+        .fold(0, |acc, (idx, hand)| acc + hand.bid * (idx + 1))
+}
+
+/// Every hand parsed twice, once with `J` as a jack and once as a joker.
+#[derive(Debug)]
+pub struct Hands {
+    regular: Vec<HandOfCards>,
+    with_jokers: Vec<HandOfCards>,
+}
+
+impl FromStr for Hands {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Hands {
+            regular: s.lines().map(|line| Part1(line).try_into()).try_collect()?,
+            with_jokers: s.lines().map(|line| Part2(line).try_into()).try_collect()?,
+        })
+    }
 }
 
 #[derive(Eq, PartialEq, Debug)]
@@ -194,3 +239,19 @@ impl CardValue for Part2<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE: &str = "\
+32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483
+";
+
+    crate::sample_tests!(super::Day7 {
+        part1_sample: part1(SAMPLE) == 6440,
+        part2_sample: part2(SAMPLE) == 5905,
+    });
+}
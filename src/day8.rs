@@ -3,8 +3,35 @@ use std::{collections::HashMap, str::FromStr};
 use anyhow::{bail, Context, Result};
 use itertools::Itertools;
 
-pub fn part1(input: &str) -> Result<i32> {
-    let game: Game = input.parse()?;
+use crate::{
+    answer::Answer,
+    solution::{Metadata, Solution},
+};
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = Game;
+
+    const METADATA: Metadata = Metadata {
+        day: 8,
+        title: "Haunted Wasteland",
+    };
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+}
+
+pub fn part1(game: &Game) -> Result<i32> {
     let finish = "ZZZ";
     let mut current = "AAA";
     let mut i = 0;
@@ -27,8 +54,7 @@ pub fn part1(input: &str) -> Result<i32> {
     Ok(count)
 }
 
-pub fn part2(input: &str) -> Result<i64> {
-    let game: Game = input.parse()?;
+pub fn part2(game: &Game) -> Result<i64> {
     let mut current = game.instructions.keys().filter(|k| k.ends_with('A')).collect_vec();
     let mut counts = (0..current.len()).map(|_| 0).collect_vec();
 
@@ -59,7 +85,7 @@ enum Move {
 }
 
 #[derive(Debug)]
-struct Game {
+pub struct Game {
     moves: Vec<Move>,
     instructions: HashMap<String, (String, String)>,
 }
@@ -108,3 +134,43 @@ fn gcd(a: i64, b: i64) -> i64 {
 fn lcm(a: i64, b: i64) -> i64 {
     (a * b) / gcd(a, b)
 }
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE: &str = "\
+RL
+
+AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)
+";
+    const SAMPLE_REPEAT: &str = "\
+LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)
+";
+    const SAMPLE_GHOSTS: &str = "\
+LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)
+";
+
+    crate::sample_tests!(super::Day8 {
+        part1_sample: part1(SAMPLE) == 2,
+        part1_sample_repeat: part1(SAMPLE_REPEAT) == 6,
+        part2_sample: part2(SAMPLE_GHOSTS) == 6,
+    });
+}
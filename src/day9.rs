@@ -3,28 +3,56 @@ use std::str::FromStr;
 use anyhow::{Context, Result};
 use itertools::Itertools;
 
-pub fn part1(input: &str) -> Result<i32> {
+use crate::{
+    answer::Answer,
+    solution::{Metadata, Solution},
+};
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input = Items;
+
+    const METADATA: Metadata = Metadata {
+        day: 9,
+        title: "Mirage Maintenance",
+    };
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+}
+
+pub fn part1(input: &Items) -> Result<i32> {
     input
-        .parse::<Items>()?
         .0
-        .into_iter()
+        .iter()
+        .cloned()
         .map(|item| -> Result<i32> { Ok(item.fill()?.extrapolate_right()) })
         .try_fold(0, |a, b| b.map(|b| b + a))
 }
 
-pub fn part2(input: &str) -> Result<i32> {
+pub fn part2(input: &Items) -> Result<i32> {
     input
-        .parse::<Items>()?
         .0
-        .into_iter()
+        .iter()
+        .cloned()
         .map(|item| -> Result<i32> { Ok(item.fill()?.extrapolate_left()) })
         .try_fold(0, |a, b| b.map(|b| b + a))
 }
 
 #[derive(Debug)]
-struct Items(Vec<Item>);
+pub struct Items(Vec<Item>);
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Item {
     sequences: Vec<Vec<i32>>,
 }
@@ -38,7 +66,7 @@ impl Item {
                     .context("There to be at least one sequence")?
                     .iter()
                     .tuple_windows()
-                    .map(|(a, b)| (b - a))
+                    .map(|(a, b)| b - a)
                     .collect(),
             );
         }
@@ -87,3 +115,17 @@ impl FromStr for Items {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE: &str = "\
+0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45
+";
+
+    crate::sample_tests!(super::Day9 {
+        part1_sample: part1(SAMPLE) == 114,
+        part2_sample: part2(SAMPLE) == 2,
+    });
+}
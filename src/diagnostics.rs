@@ -0,0 +1,27 @@
+use std::io::IsTerminal;
+
+use tracing::Level;
+use tracing_subscriber::fmt::format::FmtSpan;
+
+/// Environment variable enabling diagnostics like `--trace` does, unless it is empty or `0`.
+pub const TRACE_ENV: &str = "AOC_TRACE";
+
+/// Whether diagnostics are requested by the `--trace` flag or the [`TRACE_ENV`] environment variable.
+pub fn enabled(flag: bool) -> bool {
+    flag || std::env::var_os(TRACE_ENV).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+/// Prints the spans and events of the runner and the days to stderr, with the time spent in a span once it closes.
+///
+/// Days report through the `tracing` macros, ie. `tracing::debug_span!("expand").entered()` around a step worth
+/// timing, and never print to stdout themselves.
+pub fn init() {
+    // Fails only when a subscriber is already installed, which then keeps receiving everything.
+    let _ = tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_ansi(std::io::stderr().is_terminal())
+        .with_max_level(Level::TRACE)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_target(false)
+        .try_init();
+}
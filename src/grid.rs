@@ -0,0 +1,399 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use anyhow::{anyhow, bail, Result};
+
+/// Position in a [`Grid`], `x` is the column and `y` the row counted from the top left corner.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Coord {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Coord {
+    pub fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+
+    /// Moves a single step in `direction`, `None` when leaving the top or left edge.
+    pub fn step(self, direction: Direction) -> Option<Coord> {
+        let (dx, dy) = direction.offset();
+        Some(Coord {
+            x: self.x.checked_add_signed(dx)?,
+            y: self.y.checked_add_signed(dy)?,
+        })
+    }
+
+    pub fn manhattan_distance(self, other: Coord) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+}
+
+impl Display for Coord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// All directions clockwise starting from [`Direction::Up`].
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Right => Direction::Left,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+        }
+    }
+
+    pub fn turn_right(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn turn_left(self) -> Direction {
+        self.turn_right().opposite()
+    }
+
+    pub fn is_horizontal(self) -> bool {
+        matches!(self, Direction::Left | Direction::Right)
+    }
+
+    /// Character pointing in the direction, for drawing paths.
+    pub fn arrow(self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+        }
+    }
+
+    /// Change of `(x, y)` when moving one step.
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Right => (1, 0),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+        }
+    }
+}
+
+/// Offsets of the 8 neighbours clockwise starting from the one above.
+const NEIGHBOURS8: [(isize, isize); 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)];
+
+/// Rectangular grid stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Builds a grid from its rows, which all need to have the same length.
+    pub fn from_rows(rows: impl IntoIterator<Item = Vec<T>>) -> Result<Self> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+        for row in rows {
+            match width {
+                None => width = Some(row.len()),
+                Some(width) if width != row.len() => {
+                    bail!("Row {} has {} columns, expected {}", height, row.len(), width)
+                }
+                Some(_) => {}
+            }
+            cells.extend(row);
+            height += 1;
+        }
+        Ok(Self {
+            width: width.unwrap_or_default(),
+            height,
+            cells,
+        })
+    }
+
+    /// Parses a character map, `parse` is called for every character with its position.
+    pub fn parse_with(s: &str, mut parse: impl FnMut(Coord, char) -> Result<T>) -> Result<Self> {
+        Self::from_rows(
+            s.lines()
+                .enumerate()
+                .map(|(y, line)| {
+                    line.chars()
+                        .enumerate()
+                        .map(|(x, c)| parse(Coord { x, y }, c))
+                        .collect::<Result<Vec<_>>>()
+                })
+                .collect::<Result<Vec<_>>>()?,
+        )
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, coord: Coord) -> bool {
+        coord.x < self.width && coord.y < self.height
+    }
+
+    pub fn get(&self, coord: Coord) -> Option<&T> {
+        self.contains(coord)
+            .then(|| &self.cells[coord.y * self.width + coord.x])
+    }
+
+    pub fn get_mut(&mut self, coord: Coord) -> Option<&mut T> {
+        self.contains(coord)
+            .then(|| &mut self.cells[coord.y * self.width + coord.x])
+    }
+
+    /// The neighbour in `direction`, `None` when it is outside of the grid.
+    pub fn neighbour(&self, coord: Coord, direction: Direction) -> Option<Coord> {
+        coord.step(direction).filter(|c| self.contains(*c))
+    }
+
+    /// Neighbours sharing an edge, clockwise starting from the one above.
+    pub fn neighbours4(&self, coord: Coord) -> impl Iterator<Item = (Direction, Coord)> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| Some((direction, self.neighbour(coord, direction)?)))
+    }
+
+    /// Neighbours sharing an edge or a corner, clockwise starting from the one above.
+    pub fn neighbours8(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        NEIGHBOURS8.into_iter().filter_map(move |(dx, dy)| {
+            Some(Coord {
+                x: coord.x.checked_add_signed(dx)?,
+                y: coord.y.checked_add_signed(dy)?,
+            })
+            .filter(|c| self.contains(*c))
+        })
+    }
+
+    /// The first position holding a value matching `predicate`, searching row by row.
+    pub fn position(&self, predicate: impl FnMut(&T) -> bool) -> Option<Coord> {
+        self.cells.iter().position(predicate).map(|idx| self.coord(idx))
+    }
+
+    /// All positions row by row.
+    pub fn coords(&self) -> impl Iterator<Item = Coord> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Coord { x, y }))
+    }
+
+    /// All positions and their values row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(idx, value)| (self.coord(idx), value))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on a zero width.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1)).take(self.height)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Mirrors the grid along its main diagonal, rows become columns.
+    pub fn transposed(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: self.columns().flatten().cloned().collect(),
+        }
+    }
+
+    pub fn rotated_clockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: (0..self.width)
+                .flat_map(|x| (0..self.height).rev().map(move |y| self[Coord { x, y }].clone()))
+                .collect(),
+        }
+    }
+
+    pub fn rotated_counter_clockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: (0..self.width)
+                .rev()
+                .flat_map(|x| (0..self.height).map(move |y| self[Coord { x, y }].clone()))
+                .collect(),
+        }
+    }
+
+    fn coord(&self, idx: usize) -> Coord {
+        Coord {
+            x: idx % self.width,
+            y: idx / self.width,
+        }
+    }
+}
+
+impl<T> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coord: Coord) -> &Self::Output {
+        assert!(
+            self.contains(coord),
+            "{} is outside of the {}x{} grid",
+            coord,
+            self.width,
+            self.height
+        );
+        &self.cells[coord.y * self.width + coord.x]
+    }
+}
+
+impl<T> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, coord: Coord) -> &mut Self::Output {
+        assert!(
+            self.contains(coord),
+            "{} is outside of the {}x{} grid",
+            coord,
+            self.width,
+            self.height
+        );
+        &mut self.cells[coord.y * self.width + coord.x]
+    }
+}
+
+/// Parses a character map with every character converted by `TryFrom<char>`.
+impl<T> FromStr for Grid<T>
+where
+    T: TryFrom<char>,
+{
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Grid::parse_with(s, |coord, c| {
+            T::try_from(c).map_err(|_| anyhow!("Invalid character '{}' at {}", c, coord))
+        })
+    }
+}
+
+/// Prints the grid row by row, without a trailing newline.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for value in row {
+                write!(f, "{}", value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "ab\ncd\nef";
+
+    #[test]
+    fn views() {
+        let grid: Grid<char> = SAMPLE.parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid.row(1), ['c', 'd']);
+        assert_eq!(grid.column(1).collect::<String>(), "bdf");
+        assert_eq!(grid.transposed().to_string(), "ace\nbdf");
+        assert_eq!(grid.rotated_clockwise().to_string(), "eca\nfdb");
+        assert_eq!(grid.rotated_counter_clockwise().to_string(), "bdf\nace");
+    }
+
+    #[test]
+    fn neighbours_stay_inside() {
+        let grid: Grid<char> = SAMPLE.parse().unwrap();
+        let corner = grid.neighbours8(Coord::new(0, 0)).map(|c| grid[c]).collect::<String>();
+        assert_eq!(corner, "bdc");
+        let edge = grid
+            .neighbours4(Coord::new(1, 1))
+            .map(|(_, c)| grid[c])
+            .collect::<String>();
+        assert_eq!(edge, "bfc");
+    }
+
+    #[test]
+    fn ragged_rows_are_rejected() {
+        assert!("ab\nc".parse::<Grid<char>>().is_err());
+    }
+}
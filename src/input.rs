@@ -0,0 +1,98 @@
+use std::{
+    collections::BTreeMap,
+    io::Read,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{Context, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    File(PathBuf),
+    Stdin,
+}
+
+impl InputSource {
+    pub fn read(&self) -> Result<String> {
+        match self {
+            InputSource::File(path) => {
+                std::fs::read_to_string(path).with_context(|| format!("Cannot read input file '{}'", path.display()))
+            }
+            InputSource::Stdin => {
+                let mut buf = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut buf)
+                    .context("Cannot read input from stdin")?;
+                Ok(buf)
+            }
+        }
+    }
+}
+
+impl FromStr for InputSource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "-" => InputSource::Stdin,
+            path => InputSource::File(PathBuf::from(path)),
+        })
+    }
+}
+
+impl std::fmt::Display for InputSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputSource::File(path) => write!(f, "{}", path.display()),
+            InputSource::Stdin => write!(f, "<stdin>"),
+        }
+    }
+}
+
+/// Resolves where the input of each day is read from.
+///
+/// Defaults to `input/<day>`, the directory can be replaced as a whole and single days can be pointed
+/// to a different file or stdin.
+#[derive(Debug, Clone)]
+pub struct Inputs {
+    dir: PathBuf,
+    overrides: BTreeMap<usize, InputSource>,
+}
+
+impl Default for Inputs {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("input"),
+            overrides: BTreeMap::new(),
+        }
+    }
+}
+
+impl Inputs {
+    pub fn set_dir(&mut self, dir: impl AsRef<Path>) {
+        self.dir = dir.as_ref().to_path_buf();
+    }
+
+    pub fn set(&mut self, day: usize, source: InputSource) {
+        self.overrides.insert(day, source);
+    }
+
+    /// Days pointed to a different file or stdin, in day order.
+    pub fn overrides(&self) -> impl Iterator<Item = (usize, &InputSource)> {
+        self.overrides.iter().map(|(day, source)| (*day, source))
+    }
+
+    pub fn source(&self, day: usize) -> InputSource {
+        self.overrides
+            .get(&day)
+            .cloned()
+            .unwrap_or_else(|| InputSource::File(self.dir.join(day.to_string())))
+    }
+
+    pub fn read(&self, day: usize) -> Result<String> {
+        self.source(day)
+            .read()
+            .with_context(|| format!("Missing input for day {}", day))
+    }
+}
@@ -0,0 +1,40 @@
+pub mod answer;
+pub mod answers;
+pub mod bench;
+pub mod cli;
+pub mod cycle;
+pub mod diagnostics;
+pub mod grid;
+pub mod input;
+pub mod registry;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod search;
+pub mod selection;
+pub mod solution;
+
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+
+pub use answer::Answer;
+pub use registry::{registry, Registry};
+pub use solution::{DynSolution, Metadata, Part, Solution};
@@ -1,4 +1,54 @@
-fn main() {
-    let run_all = std::env::args().any(|v| v == "--all");
-    aoc23::run(run_all);
+use std::process::ExitCode;
+
+use aoc23::cli::{Args, USAGE};
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    if args.help {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    if let Some(day) = args.new_day {
+        let title = args.title.as_deref().unwrap_or("TODO");
+        return match aoc23::scaffold::new_day(".", day, title) {
+            Ok(files) => {
+                println!("Created day {}:", day);
+                for file in files {
+                    println!("  {}", file.display());
+                }
+                ExitCode::SUCCESS
+            }
+            Err(err) => {
+                eprintln!("error: {:#}", err);
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    if aoc23::diagnostics::enabled(args.trace) {
+        aoc23::diagnostics::init();
+    }
+
+    let registry = aoc23::registry();
+    let config = match args.into_config(registry) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::from(2);
+        }
+    };
+
+    if aoc23::runner::run(registry, &config) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
 }
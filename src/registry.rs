@@ -0,0 +1,68 @@
+use std::sync::OnceLock;
+
+use crate::solution::{DynSolution, Erased, Solution};
+
+/// Collection of solutions ordered by day.
+#[derive(Default)]
+pub struct Registry {
+    solutions: Vec<Box<dyn DynSolution>>,
+}
+
+impl Registry {
+    /// Adds a solution, replacing any previously registered solution for the same day.
+    pub fn register<S: Solution + 'static>(&mut self) -> &mut Self {
+        let day = S::METADATA.day;
+        let solution = Box::new(Erased::<S>::new());
+        match self.solutions.binary_search_by_key(&day, |s| s.metadata().day) {
+            Ok(idx) => self.solutions[idx] = solution,
+            Err(idx) => self.solutions.insert(idx, solution),
+        }
+        self
+    }
+
+    pub fn get(&self, day: usize) -> Option<&dyn DynSolution> {
+        self.iter().find(|s| s.metadata().day == day)
+    }
+
+    pub fn last(&self) -> Option<&dyn DynSolution> {
+        self.solutions.last().map(Box::as_ref)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn DynSolution> {
+        self.solutions.iter().map(Box::as_ref)
+    }
+
+    pub fn days(&self) -> Vec<usize> {
+        self.iter().map(|s| s.metadata().day).collect()
+    }
+}
+
+/// Registry with every day implemented in this crate.
+pub fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let mut registry = Registry::default();
+        registry
+            .register::<crate::day1::Day1>()
+            .register::<crate::day2::Day2>()
+            .register::<crate::day3::Day3>()
+            .register::<crate::day4::Day4>()
+            .register::<crate::day5::Day5>()
+            .register::<crate::day6::Day6>()
+            .register::<crate::day7::Day7>()
+            .register::<crate::day8::Day8>()
+            .register::<crate::day9::Day9>()
+            .register::<crate::day10::Day10>()
+            .register::<crate::day11::Day11>()
+            .register::<crate::day12::Day12>()
+            .register::<crate::day13::Day13>()
+            .register::<crate::day14::Day14>()
+            .register::<crate::day15::Day15>()
+            .register::<crate::day16::Day16>()
+            .register::<crate::day17::Day17>()
+            .register::<crate::day18::Day18>()
+            .register::<crate::day19::Day19>()
+            .register::<crate::day20::Day20>();
+        registry
+    })
+}
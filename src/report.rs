@@ -0,0 +1,221 @@
+use std::{io::Write, str::FromStr, time::Duration};
+
+use anyhow::{bail, Result};
+use colored::{ColoredString, Colorize};
+
+use crate::{answer::Answer, answers::Status, bench::Timings, solution::Part};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "text" => Format::Text,
+            "json" => Format::Json,
+            "csv" => Format::Csv,
+            v => bail!("Invalid format '{}', expected text, json or csv", v),
+        })
+    }
+}
+
+/// Outcome of running a single part.
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub day: usize,
+    pub part: Part,
+    pub answer: Option<Answer>,
+    pub expected: Option<Answer>,
+    pub status: Status,
+    pub error: Option<String>,
+    pub elapsed: Duration,
+    /// Only present when benchmarking.
+    pub timings: Option<Timings>,
+}
+
+impl Record {
+    pub fn print_text(&self) {
+        let elapsed = self.elapsed.as_secs_f64();
+        match (&self.answer, &self.error) {
+            (Some(answer), _) => {
+                println!(
+                    "d{:0>2}p{} answer is: '{}'{} and took: {:}{}",
+                    self.day,
+                    self.part.number(),
+                    answer.to_string().green(),
+                    match self.status {
+                        Status::Ok => String::new(),
+                        status => format!(" [{}]", colored_status(status)),
+                    },
+                    (elapsed * 1000.0).to_string().yellow(),
+                    "ms".yellow()
+                );
+                if let (Status::Fail, Some(expected)) = (self.status, &self.expected) {
+                    println!("Expected answer: '{}'", expected);
+                }
+            }
+            (None, error) => {
+                println!(
+                    "d{:0>2}p{} {} and took: {:}{}",
+                    self.day,
+                    self.part.number(),
+                    "FAILED".bright_red(),
+                    (elapsed * 1000.0).to_string().yellow(),
+                    "ms".yellow()
+                );
+                println!("Failure message: {}", error.as_deref().unwrap_or_default());
+            }
+        }
+    }
+}
+
+fn colored_status(status: Status) -> ColoredString {
+    match status {
+        Status::Pass | Status::Ok => status.to_string().green(),
+        Status::Fail | Status::Error => status.to_string().bright_red(),
+        Status::Unknown => status.to_string().yellow(),
+    }
+}
+
+pub fn print_check_summary(records: &[Record]) {
+    let count = |status: Status| records.iter().filter(|r| r.status == status).count();
+    println!(
+        "Checked {} parts: {} passed, {} failed, {} errors, {} unknown",
+        records.len(),
+        count(Status::Pass).to_string().green(),
+        count(Status::Fail).to_string().bright_red(),
+        count(Status::Error).to_string().bright_red(),
+        count(Status::Unknown).to_string().yellow()
+    );
+}
+
+/// Prints the wall-clock time of the whole run next to the time spent in each part summed up.
+pub fn print_time_summary(records: &[Record], wall_clock: Duration, jobs: usize) {
+    let summed: Duration = records.iter().map(|r| r.elapsed).sum();
+    println!(
+        "Ran {} parts on {} {} in {}{} wall-clock, {}{} summed over parts",
+        records.len(),
+        jobs,
+        if jobs == 1 { "thread" } else { "threads" },
+        millis(wall_clock).to_string().yellow(),
+        "ms".yellow(),
+        millis(summed).to_string().yellow(),
+        "ms".yellow()
+    );
+}
+
+/// Writes all records as a JSON array, one object per part.
+pub fn write_json(records: &[Record], out: &mut impl Write) -> std::io::Result<()> {
+    writeln!(out, "[")?;
+    for (idx, record) in records.iter().enumerate() {
+        write!(
+            out,
+            r#"  {{"day": {}, "part": {}, "answer": {}, "expected": {}, "status": {}, "error": {}, "time_ms": {}, "parse_ms": {}, "solve_ms": {}}}"#,
+            record.day,
+            record.part.number(),
+            json_opt_string(record.answer.as_ref().map(Answer::to_string).as_deref()),
+            json_opt_string(record.expected.as_ref().map(Answer::to_string).as_deref()),
+            json_string(&record.status.to_string()),
+            json_opt_string(record.error.as_deref()),
+            millis(record.elapsed),
+            record
+                .timings
+                .map_or("null".to_string(), |t| millis(t.parse.mean).to_string()),
+            record
+                .timings
+                .map_or("null".to_string(), |t| millis(t.solve.mean).to_string()),
+        )?;
+        writeln!(out, "{}", if idx + 1 < records.len() { "," } else { "" })?;
+    }
+    writeln!(out, "]")
+}
+
+/// Writes all records as CSV with a header row, timings of benchmarks are the means.
+pub fn write_csv(records: &[Record], out: &mut impl Write) -> std::io::Result<()> {
+    writeln!(out, "day,part,answer,expected,status,error,time_ms,parse_ms,solve_ms")?;
+    for record in records {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{}",
+            record.day,
+            record.part.number(),
+            csv_field(&record.answer.as_ref().map(Answer::to_string).unwrap_or_default()),
+            csv_field(&record.expected.as_ref().map(Answer::to_string).unwrap_or_default()),
+            record.status,
+            csv_field(record.error.as_deref().unwrap_or_default()),
+            millis(record.elapsed),
+            record
+                .timings
+                .map_or(String::new(), |t| millis(t.parse.mean).to_string()),
+            record
+                .timings
+                .map_or(String::new(), |t| millis(t.solve.mean).to_string()),
+        )?;
+    }
+    Ok(())
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn json_opt_string(value: Option<&str>) -> String {
+    value.map_or("null".to_string(), json_string)
+}
+
+fn json_string(value: &str) -> String {
+    let mut res = String::with_capacity(value.len() + 2);
+    res.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c if c.is_control() => res.push_str(&format!("\\u{:04x}", c as u32)),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_strings() {
+        assert_eq!(json_string("42"), r#""42""#);
+        assert_eq!(json_string(r#"say "hi" \o/"#), r#""say \"hi\" \\o/""#);
+        assert_eq!(json_string("a\nb\r\tc"), r#""a\nb\r\tc""#);
+        assert_eq!(json_string("\u{0}\u{1b}[0m\u{7f}"), r#""\u0000\u001b[0m\u007f""#);
+        assert_eq!(json_string("über ✓"), "\"über ✓\"");
+    }
+
+    #[test]
+    fn csv_fields() {
+        assert_eq!(csv_field("42"), "42");
+        assert_eq!(csv_field("a b\tc"), "a b\tc");
+        assert_eq!(csv_field("1,2"), r#""1,2""#);
+        assert_eq!(csv_field(r#"say "hi""#), r#""say ""hi""""#);
+        assert_eq!(csv_field("a\nb"), "\"a\nb\"");
+        assert_eq!(csv_field("a\rb"), "\"a\rb\"");
+    }
+}
@@ -0,0 +1,359 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::Instant,
+};
+
+use colored::Colorize;
+
+use crate::{
+    answers::{Answers, Status},
+    bench,
+    input::Inputs,
+    registry::Registry,
+    report::{self, Format, Record},
+    selection::Selection,
+    solution::{self, DynSolution, Part},
+};
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub selection: Selection,
+    pub inputs: Inputs,
+    /// Compare answers with the ones stored in `answers_dir`.
+    pub check: bool,
+    pub answers_dir: PathBuf,
+    /// Run every part this many times and print timing statistics.
+    pub bench: Option<usize>,
+    /// Number of parts run at the same time.
+    pub jobs: usize,
+    /// Draw how each part is solved after its answer, only used with the text format.
+    pub visualize: bool,
+    pub format: Format,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            selection: Selection::default(),
+            inputs: Inputs::default(),
+            check: false,
+            answers_dir: PathBuf::from("answers"),
+            bench: None,
+            jobs: 1,
+            visualize: false,
+            format: Format::default(),
+        }
+    }
+}
+
+/// Runs every selected day and part, returns whether all of them succeeded.
+pub fn run(registry: &Registry, config: &Config) -> bool {
+    let start = Instant::now();
+    let records = collect(registry, config);
+    let wall_clock = start.elapsed();
+
+    let mut stdout = std::io::stdout().lock();
+    let written = match config.format {
+        Format::Text => {
+            if records.len() > 1 {
+                report::print_time_summary(&records, wall_clock, config.jobs);
+            }
+            if let Some(iterations) = config.bench {
+                bench::print_summary(&records, iterations);
+            }
+            if config.check {
+                report::print_check_summary(&records);
+            }
+            Ok(())
+        }
+        Format::Json => report::write_json(&records, &mut stdout),
+        Format::Csv => report::write_csv(&records, &mut stdout),
+    };
+    if let Err(err) = written {
+        eprintln!("error: Cannot write results: {}", err);
+        return false;
+    }
+
+    !records.iter().any(|r| matches!(r.status, Status::Fail | Status::Error))
+}
+
+/// Solutions may recurse deeply, give workers as much stack as the main thread usually has.
+const WORKER_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Work for a single selected day, in day order.
+enum Day {
+    /// The input or answers could not be read, no part is run.
+    Failed {
+        day: usize,
+        error: String,
+        records: Vec<Record>,
+    },
+    /// Indices of the day's parts in the job list.
+    Jobs(std::ops::Range<usize>),
+}
+
+struct Job<'a> {
+    solution: &'a dyn DynSolution,
+    input: &'a str,
+    part: Part,
+    answers: Option<&'a Answers>,
+}
+
+/// Runs every selected day and part on `config.jobs` threads.
+///
+/// Records are returned in day order and text output is printed in the same order, as soon as all previous parts
+/// have finished.
+pub fn collect(registry: &Registry, config: &Config) -> Vec<Record> {
+    let Some(last_day) = registry.last().map(|s| s.metadata().day) else {
+        return Vec::new();
+    };
+
+    let mut loaded = Vec::new();
+    for solution in registry.iter() {
+        let day = solution.metadata().day;
+        if !config.selection.includes_day(day, last_day) {
+            continue;
+        }
+        let answers = if config.check {
+            Answers::load(&config.answers_dir, day).map(Some)
+        } else {
+            Ok(None)
+        };
+        loaded.push((
+            solution,
+            config.inputs.read(day).and_then(|input| Ok((input, answers?))),
+        ));
+    }
+
+    let mut days = Vec::new();
+    let mut jobs = Vec::new();
+    for (solution, loaded) in &loaded {
+        let day = solution.metadata().day;
+        let parts = [Part::One, Part::Two]
+            .into_iter()
+            .filter(|part| config.selection.includes_part(*part));
+        match loaded {
+            Ok((input, answers)) => {
+                let first = jobs.len();
+                jobs.extend(parts.map(|part| Job {
+                    solution: *solution,
+                    input,
+                    part,
+                    answers: answers.as_ref(),
+                }));
+                days.push(Day::Jobs(first..jobs.len()));
+            }
+            Err(err) => {
+                let error = format!("{:#}", err);
+                let records = parts
+                    .map(|part| Record {
+                        day,
+                        part,
+                        answer: None,
+                        expected: None,
+                        status: Status::Error,
+                        error: Some(error.clone()),
+                        elapsed: Default::default(),
+                        timings: None,
+                    })
+                    .collect();
+                days.push(Day::Failed { day, error, records });
+            }
+        }
+    }
+
+    let run_job = &|job: &Job| run_part(job.solution, job.input, job.part, job.answers, config.bench);
+    let next_job = &AtomicUsize::new(0);
+    let jobs = &jobs;
+    let workers = if config.jobs > 1 {
+        config.jobs.min(jobs.len())
+    } else {
+        0
+    };
+    // Parts running at the same time share the CPUs instead of each spreading over all of them.
+    let threads_per_worker = thread::available_parallelism().map_or(1, usize::from) / workers.max(1);
+
+    thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..workers {
+            let sender = sender.clone();
+            thread::Builder::new()
+                .stack_size(WORKER_STACK_SIZE)
+                .spawn_scoped(scope, move || {
+                    solution::limit_threads(threads_per_worker);
+                    loop {
+                        let idx = next_job.fetch_add(1, Ordering::Relaxed);
+                        let Some(job) = jobs.get(idx) else {
+                            break;
+                        };
+                        if sender.send((idx, run_job(job))).is_err() {
+                            break;
+                        }
+                    }
+                })
+                .expect("Cannot spawn worker thread");
+        }
+        drop(sender);
+
+        let mut finished: Vec<Option<Record>> = jobs.iter().map(|_| None).collect();
+        let mut records = Vec::new();
+        for day in days {
+            match day {
+                Day::Failed {
+                    day,
+                    error,
+                    records: failed,
+                } => {
+                    if config.format == Format::Text {
+                        println!("d{:0>2} {}", day, "FAILED".bright_red());
+                        println!("Failure message: {}", error);
+                    }
+                    records.extend(failed);
+                }
+                Day::Jobs(range) => {
+                    for idx in range {
+                        let record = if workers == 0 {
+                            run_job(&jobs[idx])
+                        } else {
+                            loop {
+                                if let Some(record) = finished[idx].take() {
+                                    break record;
+                                }
+                                let (done, record) = receiver.recv().expect("Worker thread panicked");
+                                finished[done] = Some(record);
+                            }
+                        };
+                        if config.format == Format::Text {
+                            record.print_text();
+                            if config.visualize && record.error.is_none() {
+                                visualize(&jobs[idx]);
+                            }
+                        }
+                        records.push(record);
+                    }
+                }
+            }
+        }
+        records
+    })
+}
+
+fn visualize(job: &Job) {
+    let mut stdout = std::io::stdout().lock();
+    let res = job
+        .solution
+        .parse(job.input)
+        .and_then(|input| job.solution.visualize(input.as_ref(), job.part, &mut stdout));
+    if let Err(err) = res {
+        println!("Cannot visualize: {:#}", err);
+    }
+}
+
+/// Runs a single part, or benchmarks it when `bench` is set.
+fn run_part(
+    solution: &dyn DynSolution,
+    input: &str,
+    part: Part,
+    answers: Option<&Answers>,
+    bench: Option<usize>,
+) -> Record {
+    let start = Instant::now();
+    let (res, timings) = match bench {
+        Some(iterations) => match bench::bench(solution, input, part, iterations) {
+            Ok((answer, timings)) => (Ok(answer), Some(timings)),
+            Err(err) => (Err(err), None),
+        },
+        None => (solution.run(input, part), None),
+    };
+    let elapsed = timings.map_or(start.elapsed(), |t| t.parse.mean + t.solve.mean);
+
+    let expected = answers.and_then(|a| a.get(part)).cloned();
+    let (answer, error, status) = match res {
+        Ok(answer) => {
+            let status = answers.map_or(Status::Ok, |a| a.check(part, &answer));
+            (Some(answer), None, status)
+        }
+        Err(err) => (None, Some(format!("{:#}", err)), Status::Error),
+    };
+
+    Record {
+        day: solution.metadata().day,
+        part,
+        answer,
+        expected,
+        status,
+        error,
+        elapsed,
+        timings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        answer::Answer,
+        input::InputSource,
+        selection::Days,
+        solution::{Metadata, Solution},
+    };
+
+    /// Part 1 of earlier days takes longer, so with several jobs the parts finish out of day order.
+    struct Slow<const DAY: usize>;
+
+    impl<const DAY: usize> Solution for Slow<DAY> {
+        type Input = ();
+
+        const METADATA: Metadata = Metadata {
+            day: DAY,
+            title: "Slow",
+        };
+
+        fn parse(_input: &str) -> anyhow::Result<Self::Input> {
+            Ok(())
+        }
+
+        fn part1(_input: &Self::Input) -> anyhow::Result<Answer> {
+            thread::sleep(Duration::from_millis(20 * (4 - DAY as u64)));
+            Ok((DAY * 10 + 1).into())
+        }
+
+        fn part2(_input: &Self::Input) -> anyhow::Result<Answer> {
+            Ok((DAY * 10 + 2).into())
+        }
+    }
+
+    #[test]
+    fn records_stay_in_day_order() {
+        let mut registry = Registry::default();
+        registry
+            .register::<Slow<1>>()
+            .register::<Slow<2>>()
+            .register::<Slow<3>>();
+        let mut config = Config {
+            format: Format::Json,
+            ..Default::default()
+        };
+        config.selection.days = Days::All;
+        for day in 1..=3 {
+            config.inputs.set(day, InputSource::File("Cargo.toml".into()));
+        }
+
+        let expected = [11, 12, 21, 22, 31, 32].map(Answer::Int);
+        for jobs in [1, 2, 4] {
+            config.jobs = jobs;
+            let answers = collect(&registry, &config)
+                .into_iter()
+                .map(|record| record.answer.unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(answers, expected, "with {} jobs", jobs);
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+
+const TEMPLATE: &str = r#"use std::str::FromStr;
+
+use anyhow::{bail, Result};
+
+use crate::{
+    answer::Answer,
+    solution::{Metadata, Solution},
+};
+
+pub struct Day{day};
+
+impl Solution for Day{day} {
+    type Input = Puzzle;
+
+    const METADATA: Metadata = Metadata {
+        day: {day},
+        title: "{title}",
+    };
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Self::Input) -> Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+}
+
+pub fn part1(_puzzle: &Puzzle) -> Result<i64> {
+    bail!("Part 1 is not solved yet")
+}
+
+pub fn part2(_puzzle: &Puzzle) -> Result<i64> {
+    bail!("Part 2 is not solved yet")
+}
+
+#[derive(Debug)]
+pub struct Puzzle {
+    pub lines: Vec<String>,
+}
+
+impl FromStr for Puzzle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Puzzle {
+            lines: s.lines().map(str::to_string).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    const SAMPLE: &str = "\
+";
+
+    crate::sample_tests!(super::Day{day} {
+        #[ignore = "sample not filled in yet"]
+        part1_sample: part1(SAMPLE) == 0,
+        #[ignore = "sample not filled in yet"]
+        part2_sample: part2(SAMPLE) == 0,
+    });
+}
+"#;
+
+const ANSWERS_TEMPLATE: &str = "# part1 = 0
+# part2 = 0
+";
+
+/// Creates the files of a new day under the crate at `root` and registers it, returns the created or changed files.
+///
+/// Existing input and answers files are kept, an existing module is an error.
+pub fn new_day(root: impl AsRef<Path>, day: usize, title: &str) -> Result<Vec<PathBuf>> {
+    let root = root.as_ref();
+    if day == 0 {
+        bail!("Days start at 1");
+    }
+    let module = root.join("src").join(format!("day{}.rs", day));
+    if module.exists() {
+        bail!("Day {} already exists at '{}'", day, module.display());
+    }
+
+    let lib = root.join("src").join("lib.rs");
+    let registry = root.join("src").join("registry.rs");
+    let lib_source = add_module(&read(&lib)?, day)?;
+    let registry_source = add_registration(&read(&registry)?, day)?;
+
+    let mut changed = Vec::new();
+    write(&module, &render(day, title))?;
+    changed.push(module);
+    write(&lib, &lib_source)?;
+    changed.push(lib);
+    write(&registry, &registry_source)?;
+    changed.push(registry);
+
+    for (path, contents) in [
+        (root.join("input").join(day.to_string()), ""),
+        (root.join("answers").join(format!("{}.toml", day)), ANSWERS_TEMPLATE),
+    ] {
+        if !path.exists() {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).with_context(|| format!("Cannot create '{}'", dir.display()))?;
+            }
+            write(&path, contents)?;
+            changed.push(path);
+        }
+    }
+
+    Ok(changed)
+}
+
+fn render(day: usize, title: &str) -> String {
+    TEMPLATE
+        .replace("{day}", &day.to_string())
+        .replace("{title}", &title.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Adds `pub mod day<N>;` to the block of day modules in `lib.rs`, ordered by day.
+fn add_module(source: &str, day: usize) -> Result<String> {
+    let mut lines: Vec<String> = source.lines().map(str::to_string).collect();
+    let module_day =
+        |line: &str| -> Option<usize> { line.strip_prefix("pub mod day")?.strip_suffix(';')?.parse().ok() };
+    let is_day = |line: &String| module_day(line).is_some();
+    let first = lines.iter().position(is_day).context("No day modules in lib.rs")?;
+    let end = lines[first..]
+        .iter()
+        .position(|line| !is_day(line))
+        .map_or(lines.len(), |idx| first + idx);
+
+    lines.insert(end, format!("pub mod day{};", day));
+    lines[first..=end].sort_by_key(|line| module_day(line));
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Adds the day to the chain of `.register::<...>()` calls in `registry.rs`, ordered by day.
+fn add_registration(source: &str, day: usize) -> Result<String> {
+    let mut lines: Vec<String> = source.lines().map(str::to_string).collect();
+    let registered_day = |line: &str| -> Option<usize> {
+        line.trim()
+            .strip_prefix(".register::<crate::day")?
+            .split_once("::")?
+            .0
+            .parse()
+            .ok()
+    };
+    let registrations = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, line)| registered_day(line).map(|day| (idx, day)))
+        .collect::<Vec<_>>();
+    let (Some(&(first, _)), Some(&(last, _))) = (registrations.first(), registrations.last()) else {
+        bail!("No registered days in registry.rs");
+    };
+    let idx = registrations
+        .iter()
+        .find(|(_, registered)| *registered > day)
+        .map_or(last + 1, |(idx, _)| *idx);
+
+    let indent = lines[first][..lines[first].len() - lines[first].trim_start().len()].to_string();
+    lines.insert(idx, format!("{}.register::<crate::day{}::Day{}>()", indent, day, day));
+    for line in &mut lines[first..=last + 1] {
+        if let Some(stripped) = line.strip_suffix(';') {
+            *line = stripped.to_string();
+        }
+    }
+    lines[last + 1].push(';');
+    Ok(lines.join("\n") + "\n")
+}
+
+fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("Cannot read '{}'", path.display()))
+}
+
+fn write(path: &Path, contents: &str) -> Result<()> {
+    fs::write(path, contents).with_context(|| format!("Cannot write '{}'", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module_is_added_in_day_order() {
+        let source = "pub mod cli;\n\npub mod day1;\npub mod day2;\n\npub use cli::Args;\n";
+        assert_eq!(
+            add_module(source, 10).unwrap(),
+            "pub mod cli;\n\npub mod day1;\npub mod day2;\npub mod day10;\n\npub use cli::Args;\n"
+        );
+    }
+
+    #[test]
+    fn registration_is_added_in_day_order() {
+        let source = "        registry\n            .register::<crate::day1::Day1>()\n            .register::<crate::day2::Day2>();\n        registry\n";
+        assert_eq!(
+            add_registration(source, 3).unwrap(),
+            "        registry\n            .register::<crate::day1::Day1>()\n            .register::<crate::day2::Day2>()\n            .register::<crate::day3::Day3>();\n        registry\n"
+        );
+    }
+}
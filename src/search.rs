@@ -0,0 +1,304 @@
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Cheapest route found by a search, `states` runs from a start to the goal, both included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S, C> {
+    pub cost: C,
+    pub states: Vec<S>,
+}
+
+impl<S, C> Path<S, C> {
+    pub fn start(&self) -> &S {
+        self.states.first().expect("A path holds at least its start")
+    }
+
+    pub fn goal(&self) -> &S {
+        self.states.last().expect("A path holds at least its goal")
+    }
+}
+
+/// Breadth first search, the cost of the returned path is its number of steps.
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut nodes = Nodes::default();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if let Some(idx) = nodes.visit(start, None, 0) {
+            queue.push_back(idx);
+        }
+    }
+
+    while let Some(idx) = queue.pop_front() {
+        if is_goal(&nodes.states[idx]) {
+            return Some(nodes.path(idx));
+        }
+        let cost = nodes.costs[idx] + 1;
+        for next in successors(&nodes.states[idx]) {
+            if let Some(next) = nodes.visit(next, Some(idx), cost) {
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Every state reachable from the starts with the number of steps needed to reach it.
+pub fn reachable<S, I>(starts: impl IntoIterator<Item = S>, mut successors: impl FnMut(&S) -> I) -> HashMap<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut distances = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if let Entry::Vacant(entry) = distances.entry(start.clone()) {
+            entry.insert(0);
+            queue.push_back((start, 0));
+        }
+    }
+
+    while let Some((state, distance)) = queue.pop_front() {
+        for next in successors(&state) {
+            if let Entry::Vacant(entry) = distances.entry(next.clone()) {
+                entry.insert(distance + 1);
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+    distances
+}
+
+/// Cheapest path for non-negative step costs.
+pub fn dijkstra<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(starts, successors, |_| C::default(), is_goal)
+}
+
+/// Cheapest path for non-negative step costs, exploring states with the lowest cost plus `heuristic` first.
+///
+/// The heuristic must never overestimate the remaining cost to a goal, otherwise the path may not be the cheapest.
+pub fn astar<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> C,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let (nodes, goal) = explore(starts, successors, heuristic, is_goal);
+    goal.map(|goal| nodes.path(goal))
+}
+
+/// Least cost of every state reachable from the starts, for non-negative step costs.
+pub fn dijkstra_all<S, C, I>(starts: impl IntoIterator<Item = S>, successors: impl FnMut(&S) -> I) -> HashMap<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let (nodes, _) = explore(starts, successors, |_| C::default(), |_| false);
+    nodes.states.into_iter().zip(nodes.costs).collect()
+}
+
+/// Runs A* until a goal is popped from the queue, or every reachable state is explored when there is none.
+fn explore<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> (Nodes<S, C>, Option<usize>)
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut nodes = Nodes::default();
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+        let estimate = heuristic(&start);
+        if let Some(idx) = nodes.visit(start, None, C::default()) {
+            heap.push(Candidate {
+                estimate,
+                cost: C::default(),
+                idx,
+            });
+        }
+    }
+
+    while let Some(Candidate { cost, idx, .. }) = heap.pop() {
+        if cost > nodes.costs[idx] {
+            // A cheaper way to this state was found after this candidate was queued.
+            continue;
+        }
+        if is_goal(&nodes.states[idx]) {
+            return (nodes, Some(idx));
+        }
+        for (next, step) in successors(&nodes.states[idx]) {
+            let cost = cost + step;
+            let estimate = cost + heuristic(&next);
+            if let Some(idx) = nodes.visit(next, Some(idx), cost) {
+                heap.push(Candidate { estimate, cost, idx });
+            }
+        }
+    }
+    (nodes, None)
+}
+
+/// States seen by a search with the cheapest known cost and the state it was reached from.
+struct Nodes<S, C> {
+    index: HashMap<S, usize>,
+    states: Vec<S>,
+    costs: Vec<C>,
+    parents: Vec<Option<usize>>,
+}
+
+impl<S, C> Default for Nodes<S, C> {
+    fn default() -> Self {
+        Self {
+            index: HashMap::new(),
+            states: Vec::new(),
+            costs: Vec::new(),
+            parents: Vec::new(),
+        }
+    }
+}
+
+impl<S: Clone + Eq + Hash, C: Copy + Ord> Nodes<S, C> {
+    /// Records `state` when it is new or reached cheaper than before, returns its index in that case.
+    fn visit(&mut self, state: S, parent: Option<usize>, cost: C) -> Option<usize> {
+        match self.index.entry(state) {
+            Entry::Vacant(entry) => {
+                let idx = self.states.len();
+                self.states.push(entry.key().clone());
+                self.costs.push(cost);
+                self.parents.push(parent);
+                entry.insert(idx);
+                Some(idx)
+            }
+            Entry::Occupied(entry) => {
+                let idx = *entry.get();
+                if cost < self.costs[idx] {
+                    self.costs[idx] = cost;
+                    self.parents[idx] = parent;
+                    Some(idx)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    fn path(&self, goal: usize) -> Path<S, C> {
+        let mut states = Vec::new();
+        let mut current = Some(goal);
+        while let Some(idx) = current {
+            states.push(self.states[idx].clone());
+            current = self.parents[idx];
+        }
+        states.reverse();
+        Path {
+            cost: self.costs[goal],
+            states,
+        }
+    }
+}
+
+/// Entry of the A* queue, ordered so the [`BinaryHeap`] pops the lowest estimate first.
+#[derive(Debug, PartialEq, Eq)]
+struct Candidate<C> {
+    estimate: C,
+    cost: C,
+    idx: usize,
+}
+
+impl<C: Ord> Ord for Candidate<C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .cmp(&self.estimate)
+            // Prefer candidates further along, they are closer to a goal for the same estimate.
+            .then_with(|| self.cost.cmp(&other.cost))
+    }
+}
+
+impl<C: Ord> PartialOrd for Candidate<C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Coord, Grid};
+
+    const MAZE: &str = "\
+..#....
+.##.##.
+....#..
+.####.#
+......#";
+
+    fn open_neighbours(grid: &Grid<char>, coord: Coord) -> Vec<Coord> {
+        grid.neighbours4(coord)
+            .filter(|(_, c)| grid[*c] == '.')
+            .map(|(_, c)| c)
+            .collect()
+    }
+
+    #[test]
+    fn searches_agree_on_shortest_path() {
+        let grid: Grid<char> = MAZE.parse().unwrap();
+        let goal = Coord::new(6, 0);
+        let successors = |c: &Coord| open_neighbours(&grid, *c);
+        let weighted = |c: &Coord| open_neighbours(&grid, *c).into_iter().map(|c| (c, 1));
+
+        let bfs = bfs([Coord::new(0, 0)], successors, |c| *c == goal).unwrap();
+        let dijkstra = dijkstra([Coord::new(0, 0)], weighted, |c| *c == goal).unwrap();
+        let astar = astar(
+            [Coord::new(0, 0)],
+            weighted,
+            |c| c.manhattan_distance(goal),
+            |c| *c == goal,
+        )
+        .unwrap();
+
+        assert_eq!(bfs.cost, 10);
+        assert_eq!(dijkstra.cost, 10);
+        assert_eq!(astar.cost, 10);
+        assert_eq!(bfs.states.len(), 11);
+        assert_eq!((*astar.start(), *astar.goal()), (Coord::new(0, 0), goal));
+        assert_eq!(reachable([Coord::new(0, 0)], successors)[&goal], 10);
+        assert_eq!(dijkstra_all([Coord::new(0, 0)], weighted)[&goal], 10);
+    }
+
+    #[test]
+    fn missing_path_is_none() {
+        let grid: Grid<char> = MAZE.parse().unwrap();
+        let walled_off = Coord::new(6, 3);
+        assert!(bfs([Coord::new(0, 0)], |c| open_neighbours(&grid, *c), |c| *c == walled_off).is_none());
+    }
+}
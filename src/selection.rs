@@ -0,0 +1,120 @@
+use std::{collections::BTreeSet, str::FromStr};
+
+use anyhow::{bail, Context, Result};
+
+use crate::solution::Part;
+
+/// Which days should be run. Defaults to the last implemented day.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Days {
+    #[default]
+    Last,
+    All,
+    Only(BTreeSet<usize>),
+}
+
+/// Day and part filters for the runner.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Selection {
+    pub days: Days,
+    pub part: Option<Part>,
+}
+
+impl Selection {
+    pub fn includes_day(&self, day: usize, last_day: usize) -> bool {
+        match &self.days {
+            Days::Last => day == last_day,
+            Days::All => true,
+            Days::Only(days) => days.contains(&day),
+        }
+    }
+
+    pub fn includes_part(&self, part: Part) -> bool {
+        self.part.is_none_or(|p| p == part)
+    }
+
+    /// Fails when a requested day is not in `available`.
+    pub fn validate(&self, available: &[usize]) -> Result<()> {
+        if let Days::Only(days) = &self.days {
+            if let Some(day) = days.iter().find(|day| !available.contains(day)) {
+                bail!("Day {} is not implemented", day);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Days {
+    type Err = anyhow::Error;
+
+    /// Parses a comma separated list of days and inclusive ranges, ie. `3,7,12-15`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut days = BTreeSet::new();
+        for item in s.split(',').map(str::trim) {
+            if item.is_empty() {
+                bail!("Empty day in '{}'", s);
+            }
+            match item.split_once('-') {
+                Some((start, end)) => {
+                    let start = parse_day(start)?;
+                    let end = parse_day(end)?;
+                    if start > end {
+                        bail!("Invalid day range '{}'", item);
+                    }
+                    days.extend(start..=end);
+                }
+                None => {
+                    days.insert(parse_day(item)?);
+                }
+            }
+        }
+        Ok(Days::Only(days))
+    }
+}
+
+fn parse_day(s: &str) -> Result<usize> {
+    let day = s.trim().parse().with_context(|| format!("Invalid day '{}'", s))?;
+    if day == 0 {
+        bail!("Invalid day '{}', days start at 1", s);
+    }
+    Ok(day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn only(days: impl IntoIterator<Item = usize>) -> Days {
+        Days::Only(days.into_iter().collect())
+    }
+
+    #[test]
+    fn days_lists_and_ranges() {
+        assert_eq!("5".parse::<Days>().unwrap(), only([5]));
+        assert_eq!("3,7,12-15".parse::<Days>().unwrap(), only([3, 7, 12, 13, 14, 15]));
+        assert_eq!(" 2 , 4-4,1-2".parse::<Days>().unwrap(), only([1, 2, 4]));
+    }
+
+    #[test]
+    fn invalid_days() {
+        for days in ["", "3,", "7-3", "0", "0-2", "a", "1-", "-1", "1-2-3", "1.5"] {
+            assert!(days.parse::<Days>().is_err(), "'{}' should not parse", days);
+        }
+    }
+
+    #[test]
+    fn selected_days_and_parts() {
+        let selection = Selection {
+            days: only([3, 7]),
+            part: Some(Part::Two),
+        };
+        assert!(selection.includes_day(7, 25));
+        assert!(!selection.includes_day(25, 25));
+        assert!(!selection.includes_part(Part::One));
+        assert!(selection.includes_part(Part::Two));
+        assert!(Selection::default().includes_day(25, 25));
+        assert!(!Selection::default().includes_day(24, 25));
+        assert!(selection.validate(&[3, 7]).is_ok());
+        assert!(selection.validate(&[3]).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+use std::{any::Any, cell::Cell, io::Write, marker::PhantomData, str::FromStr, thread};
+
+use anyhow::{bail, Context, Result};
+
+use crate::answer::Answer;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn number(&self) -> usize {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            v => bail!("Invalid part '{}', expected 1 or 2", v),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Metadata {
+    pub day: usize,
+    pub title: &'static str,
+}
+
+/// A single day of the puzzle. The input is parsed once and shared by both parts.
+pub trait Solution {
+    type Input: 'static;
+
+    const METADATA: Metadata;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+    fn part1(input: &Self::Input) -> Result<Answer>;
+    fn part2(input: &Self::Input) -> Result<Answer>;
+
+    /// Writes a drawing of how `part` is solved, returns `false` when the day has nothing to draw.
+    fn visualize(_input: &Self::Input, _part: Part, _out: &mut dyn Write) -> Result<bool> {
+        Ok(false)
+    }
+}
+
+/// Object safe counterpart of [`Solution`] used by the [`Registry`](crate::registry::Registry).
+pub trait DynSolution: Send + Sync {
+    fn metadata(&self) -> Metadata;
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>>;
+    /// Solves a part for input previously returned by [`DynSolution::parse`].
+    fn solve(&self, input: &dyn Any, part: Part) -> Result<Answer>;
+    /// See [`Solution::visualize`], `input` is previously returned by [`DynSolution::parse`].
+    fn visualize(&self, input: &dyn Any, part: Part, out: &mut dyn Write) -> Result<bool>;
+
+    /// Parses the input and solves a part in one go.
+    fn run(&self, input: &str, part: Part) -> Result<Answer> {
+        self.solve(self.parse(input)?.as_ref(), part)
+    }
+}
+
+pub(crate) struct Erased<S>(PhantomData<fn() -> S>);
+
+impl<S> Erased<S> {
+    pub(crate) fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<S: Solution> Erased<S> {
+    fn downcast(input: &dyn Any) -> Result<&S::Input> {
+        input
+            .downcast_ref::<S::Input>()
+            .with_context(|| format!("Input was not parsed by day {}", S::METADATA.day))
+    }
+}
+
+impl<S: Solution> DynSolution for Erased<S> {
+    fn metadata(&self) -> Metadata {
+        S::METADATA
+    }
+
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>> {
+        let _span = tracing::info_span!("parse", day = S::METADATA.day).entered();
+        Ok(Box::new(S::parse(input)?))
+    }
+
+    fn solve(&self, input: &dyn Any, part: Part) -> Result<Answer> {
+        let _span = tracing::info_span!("solve", day = S::METADATA.day, part = part.number()).entered();
+        match part {
+            Part::One => S::part1(Self::downcast(input)?),
+            Part::Two => S::part2(Self::downcast(input)?),
+        }
+    }
+
+    fn visualize(&self, input: &dyn Any, part: Part, out: &mut dyn Write) -> Result<bool> {
+        S::visualize(Self::downcast(input)?, part, out)
+    }
+}
+
+thread_local! {
+    static THREADS: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Number of threads a part running on the current thread may spread its work over.
+///
+/// Defaults to the available parallelism, the runner lowers it with [`limit_threads`] when it runs several parts at
+/// the same time.
+pub fn threads() -> usize {
+    THREADS
+        .get()
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, usize::from))
+}
+
+/// Sets what [`threads`] returns on the current thread, at least 1.
+pub fn limit_threads(threads: usize) {
+    THREADS.set(Some(threads.max(1)));
+}
+
+/// Generates a test per sample that parses the input with the given [`Solution`] and compares the answer of a part,
+/// ie. `sample_tests!(Day1 { sample: part1(SAMPLE) == 142 });`.
+#[cfg(test)]
+#[macro_export]
+macro_rules! sample_tests {
+    ($solution:ty { $($(#[$attr:meta])* $name:ident: $part:ident($input:expr) == $expected:expr),* $(,)? }) => {
+        $(
+            #[test]
+            $(#[$attr])*
+            fn $name() {
+                use $crate::solution::Solution;
+
+                let input = <$solution>::parse($input).expect("Sample should parse");
+                let answer = <$solution>::$part(&input).expect("Sample should be solved");
+                assert_eq!(answer, $crate::answer::Answer::from($expected));
+            }
+        )*
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn threads_are_limited_per_thread() {
+        let available = threads();
+        assert!(available >= 1);
+        thread::spawn(|| {
+            limit_threads(0);
+            assert_eq!(threads(), 1);
+            limit_threads(3);
+            assert_eq!(threads(), 3);
+        })
+        .join()
+        .unwrap();
+        assert_eq!(threads(), available);
+    }
+}